@@ -11,12 +11,142 @@
 
 // Importamos las herramientas necesarias del SDK de Soroban:
 // - contract: macro para marcar una estructura como contrato
+// - contracterror: macro para definir errores que el contrato puede retornar
 // - contractimpl: macro para implementar las funciones del contrato
-// - vec: tipo de vector compatible con Soroban
+// - contracttype: macro para tipos que se pasan como parámetros o se guardan
+// - Address: dirección de una cuenta o contrato (identifica a cada usuario)
 // - Env: entorno de ejecución del contrato (acceso a storage, logs, etc.)
+// - I256 / U256: enteros de 256 bits con y sin signo
 // - String: tipo String compatible con Soroban
-// - symbol_short: macro para crear identificadores cortos (máx 9 caracteres)
-use soroban_sdk::{contract, contractimpl, vec, Env, String, symbol_short};
+// - Symbol: identificador corto (lo usamos como nombre de los registros de memoria)
+// - Vec: vector compatible con Soroban
+use core::cmp::Ordering;
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, Address, Env, String, Symbol, Vec,
+    I256, U256,
+};
+
+// ============================================================================
+// ERRORES DEL CONTRATO
+// ============================================================================
+
+// El macro #[contracterror] convierte el enum en errores que el contrato puede
+// retornar. Así quien llama recibe un error legible en lugar de que la
+// transacción completa se aborte por un desbordamiento (overflow-checks = true)
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum CalculatorError {
+    Overflow = 1,            // El resultado no cabe en el tipo (por ejemplo, i32::MAX + 1)
+    DivisionByZero = 2,      // Se intentó dividir entre cero
+    NegativeExponent = 3,    // Operation::Pow recibió un exponente negativo
+    InvalidScale = 4,        // La escala de punto fijo es mayor que MAX_SCALE
+    MalformedExpression = 5, // La expresión está vacía, le faltan operandos o tiene símbolos inválidos
+    StackTooDeep = 6,        // La expresión necesita más de MAX_STACK_DEPTH niveles de pila
+    ExpressionTooLong = 7,   // La expresión supera MAX_EXPRESSION_TOKENS o MAX_EXPRESSION_LEN
+    EmptyInput = 8,          // Una función estadística recibió una lista vacía
+    TooManyValues = 9,       // Una función estadística recibió más de MAX_STAT_VALUES valores
+    OutOfDomain = 10,        // Raíz de un negativo, logaritmo de un número <= 0 o raíz de índice 0
+}
+
+// ============================================================================
+// TIPOS DEL CONTRATO
+// ============================================================================
+
+// Modo de redondeo de una división cuyo resultado no es exacto
+// Ejemplo con 7 / 2 = 3.5 y -7 / 2 = -3.5:
+//   - Trunc:    3 y -3 (hacia cero, como el operador / de Rust)
+//   - Floor:    3 y -4 (hacia menos infinito)
+//   - Ceil:     4 y -3 (hacia más infinito)
+//   - HalfEven: 4 y -4 (al más cercano; los empates van al número par)
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RoundingMode {
+    Trunc,
+    Floor,
+    Ceil,
+    HalfEven,
+}
+
+// Operación que calculate_and_store() aplica a sus dos operandos
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Operation {
+    Add,  // a + b
+    Sub,  // a - b
+    Mul,  // a × b
+    Div,  // a / b (truncando hacia cero, como RoundingMode::Trunc)
+    Mod,  // a % b
+    Pow,  // a ^ b (b no puede ser negativo)
+}
+
+// Elemento de una expresión en notación polaca inversa (RPN)
+// Ejemplo: (3 + 4) × 2 se escribe [Num(3), Num(4), Op(Add), Num(2), Op(Mul)]
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Token {
+    Num(i128),      // Un número: se apila
+    Op(Operation),  // Un operador: toma los dos últimos números y apila el resultado
+}
+
+// Registro completo de un cálculo guardado
+// Permite a un front-end mostrar, por ejemplo, "5 × 3 = 15"
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Calculation {
+    pub a: i32,                // Primer operando
+    pub operation: Operation,  // Operación aplicada
+    pub b: i32,                // Segundo operando
+    pub result: i32,           // Resultado
+}
+
+// Historial de cálculos de un usuario, guardado como buffer circular:
+// cuando se llena, cada cálculo nuevo reemplaza al más antiguo
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct History {
+    pub calculations: Vec<Calculation>,  // Hasta HISTORY_SIZE cálculos
+    pub next: u32,                       // Posición donde se escribirá el próximo
+}
+
+// Claves del storage del contrato
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    History(Address),           // Historial de cálculos de cada usuario (persistent storage)
+    Register(Address, Symbol),  // Registro de memoria con nombre de cada usuario (persistent storage)
+}
+
+// ============================================================================
+// CONSTANTES
+// ============================================================================
+
+// Cantidad máxima de cálculos que se guardan por usuario
+const HISTORY_SIZE: u32 = 10;
+
+// Vida (TTL) de los datos en persistent storage, medida en ledgers
+// (~17.280 ledgers por día con ledgers de 5 segundos)
+// Cuando al dato le quedan menos de TTL_THRESHOLD ledgers, lo extendemos a TTL_EXTEND
+const DAY_IN_LEDGERS: u32 = 17_280;
+const TTL_THRESHOLD: u32 = 7 * DAY_IN_LEDGERS;
+const TTL_EXTEND: u32 = 30 * DAY_IN_LEDGERS;
+
+// Punto fijo: un valor v con escala s representa v / 10^s
+// Por ejemplo, con STROOPS_SCALE (7 decimales, como los stroops de Stellar)
+// 1.5 se representa como 15_000_000
+// MAX_SCALE es la mayor escala cuyo 10^s cabe en i128
+pub const STROOPS_SCALE: u32 = 7;
+const MAX_SCALE: u32 = 38;
+
+// Límites de las expresiones que evalúa evaluate() / evaluate_infix()
+// MAX_STACK_DEPTH aplica a la pila de números y a la de operadores y paréntesis
+const MAX_STACK_DEPTH: usize = 32;
+const MAX_EXPRESSION_TOKENS: u32 = 128;
+const MAX_EXPRESSION_LEN: usize = 256;
+
+// Cantidad máxima de valores que aceptan las funciones estadísticas
+// (median() los copia a un arreglo de este tamaño para ordenarlos)
+const MAX_STAT_VALUES: usize = 100;
 
 // ============================================================================
 // DEFINICIÓN DEL CONTRATO
@@ -60,14 +190,29 @@ impl CalculatorContract {
     //   - env: Entorno de Soroban (requerido en todas las funciones públicas)
     //   - a: Primer número a sumar (entero de 32 bits con signo)
     //   - b: Segundo número a sumar (entero de 32 bits con signo)
-    // Retorna: La suma de a + b como i32
-    // Ejemplo: add(5, 3) retorna 8
+    // Retorna: La suma de a + b, o CalculatorError::Overflow si no cabe en i32
+    // Ejemplo: add(5, 3) retorna Ok(8)
     // ------------------------------------------------------------------------
-    pub fn add(env: Env, a: i32, b: i32) -> i32 {
-        // Operación simple: suma los dos números y retorna el resultado
-        a + b
+    pub fn add(_env: Env, a: i32, b: i32) -> Result<i32, CalculatorError> {
+        // checked_add retorna None si el resultado se desborda
+        a.checked_add(b).ok_or(CalculatorError::Overflow)
     }
     
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: subtract
+    // ------------------------------------------------------------------------
+    // Propósito: Resta dos números enteros
+    // Parámetros:
+    //   - env: Entorno de Soroban
+    //   - a: Minuendo
+    //   - b: Sustraendo
+    // Retorna: La resta a - b, o CalculatorError::Overflow si no cabe en i32
+    // Ejemplo: subtract(5, 3) retorna Ok(2)
+    // ------------------------------------------------------------------------
+    pub fn subtract(_env: Env, a: i32, b: i32) -> Result<i32, CalculatorError> {
+        a.checked_sub(b).ok_or(CalculatorError::Overflow)
+    }
+
     // ------------------------------------------------------------------------
     // FUNCIÓN: multiply
     // ------------------------------------------------------------------------
@@ -76,62 +221,1479 @@ impl CalculatorContract {
     //   - env: Entorno de Soroban
     //   - a: Primer número a multiplicar
     //   - b: Segundo número a multiplicar
-    // Retorna: El producto de a * b como i32
-    // Ejemplo: multiply(4, 6) retorna 24
+    // Retorna: El producto de a * b, o CalculatorError::Overflow si no cabe en i32
+    // Ejemplo: multiply(4, 6) retorna Ok(24)
     // ------------------------------------------------------------------------
-    pub fn multiply(env: Env, a: i32, b: i32) -> i32 {
-        // Operación simple: multiplica los dos números
-        a * b
+    pub fn multiply(_env: Env, a: i32, b: i32) -> Result<i32, CalculatorError> {
+        // checked_mul retorna None si el resultado se desborda
+        a.checked_mul(b).ok_or(CalculatorError::Overflow)
     }
     
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: divide
+    // ------------------------------------------------------------------------
+    // Propósito: Divide dos números enteros con un modo de redondeo explícito
+    // Parámetros:
+    //   - env: Entorno de Soroban
+    //   - a: Dividendo
+    //   - b: Divisor
+    //   - mode: Cómo redondear si la división no es exacta (ver RoundingMode)
+    // Retorna: El cociente redondeado, DivisionByZero si b = 0, u Overflow
+    //          (el único caso es i32::MIN / -1, que da 2^31 y no cabe en i32)
+    // Ejemplo: divide(7, 2, Floor) retorna Ok(3)
+    // ------------------------------------------------------------------------
+    pub fn divide(_env: Env, a: i32, b: i32, mode: RoundingMode) -> Result<i32, CalculatorError> {
+        // Dividimos en i128 (donde 2^31 sí cabe) y luego verificamos que el
+        // resultado vuelva a caber en i32
+        let quotient = divide_rounded(a as i128, b as i128, mode)?;
+        i32::try_from(quotient).map_err(|_| CalculatorError::Overflow)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: modulo
+    // ------------------------------------------------------------------------
+    // Propósito: Calcula el resto de la división a / b
+    // Parámetros:
+    //   - env: Entorno de Soroban
+    //   - a: Dividendo
+    //   - b: Divisor
+    // Retorna: El resto (con el signo de a, como el operador % de Rust),
+    //          o DivisionByZero si b = 0
+    // Ejemplo: modulo(-7, 3) retorna Ok(-1)
+    // ------------------------------------------------------------------------
+    pub fn modulo(_env: Env, a: i32, b: i32) -> Result<i32, CalculatorError> {
+        if b == 0 {
+            return Err(CalculatorError::DivisionByZero);
+        }
+
+        // i32::MIN % -1 es 0: el cociente se desborda, pero el resto no.
+        // wrapping_rem da ese 0 en lugar de fallar como checked_rem
+        Ok(a.wrapping_rem(b))
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: pow
+    // ------------------------------------------------------------------------
+    // Propósito: Eleva un número a una potencia entera no negativa
+    // Parámetros:
+    //   - env: Entorno de Soroban
+    //   - base: Base
+    //   - exp: Exponente (u32, por lo que nunca es negativo)
+    // Retorna: base^exp, o CalculatorError::Overflow si no cabe en i32
+    // Ejemplo: pow(2, 10) retorna Ok(1024)
+    // ------------------------------------------------------------------------
+    pub fn pow(_env: Env, base: i32, exp: u32) -> Result<i32, CalculatorError> {
+        base.checked_pow(exp).ok_or(CalculatorError::Overflow)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: abs
+    // ------------------------------------------------------------------------
+    // Propósito: Valor absoluto de un número
+    // Retorna: |a|, o CalculatorError::Overflow para i32::MIN (2^31 no cabe)
+    // Ejemplo: abs(-5) retorna Ok(5)
+    // ------------------------------------------------------------------------
+    pub fn abs(_env: Env, a: i32) -> Result<i32, CalculatorError> {
+        a.checked_abs().ok_or(CalculatorError::Overflow)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: negate
+    // ------------------------------------------------------------------------
+    // Propósito: Cambia el signo de un número
+    // Retorna: -a, o CalculatorError::Overflow para i32::MIN
+    // Ejemplo: negate(5) retorna Ok(-5)
+    // ------------------------------------------------------------------------
+    pub fn negate(_env: Env, a: i32) -> Result<i32, CalculatorError> {
+        a.checked_neg().ok_or(CalculatorError::Overflow)
+    }
+
     // ------------------------------------------------------------------------
     // FUNCIÓN: calculate_and_store
     // ------------------------------------------------------------------------
-    // Propósito: Suma dos números Y guarda el resultado en el storage
+    // Propósito: Aplica una operación a dos números Y guarda el cálculo en
+    //            el historial del usuario
     // Parámetros:
     //   - env: Entorno de Soroban (usado para acceder al storage)
+    //   - user: Usuario dueño del historial (debe firmar la transacción)
     //   - a: Primer número
+    //   - operation: Operación a aplicar (Add, Sub, Mul, Div, Mod, Pow)
     //   - b: Segundo número
-    // Retorna: El resultado de la suma
+    // Retorna: El resultado, o el CalculatorError de la operación
     // 
     // IMPORTANTE: Esta función demuestra cómo PERSISTIR datos en blockchain
-    // El resultado se guarda y puede ser recuperado después con get_last_calculation()
+    // El cálculo completo (operandos, operación y resultado) se guarda en el
+    // historial del usuario y puede recuperarse con get_last_calculation() o
+    // get_history(). Cada usuario tiene su propio historial, así que nadie
+    // pisa los resultados de otro. Si la operación falla no se guarda nada
     // ------------------------------------------------------------------------
-    pub fn calculate_and_store(env: Env, a: i32, b: i32) -> i32 {
-        // Paso 1: Calculamos la suma
-        let result = a + b;
+    pub fn calculate_and_store(
+        env: Env,
+        user: Address,
+        a: i32,
+        operation: Operation,
+        b: i32,
+    ) -> Result<i32, CalculatorError> {
+        // Paso 1: Solo el usuario puede escribir en su historial
+        user.require_auth();
+
+        // Paso 2: Calculamos (el operador ? retorna el error si lo hay)
+        let result = apply_operation(a, operation, b)?;
         
-        // Paso 2: Guardamos el resultado en el storage
-        // - env.storage().instance() accede al almacenamiento de esta instancia
-        // - .set() guarda un valor con una clave
-        // - symbol_short!("last_calc") crea la clave (máximo 9 caracteres)
-        // - &result es el valor que queremos guardar
-        env.storage().instance().set(&symbol_short!("last_calc"), &result);
+        // Paso 3: Guardamos el cálculo en el buffer circular del usuario
+        // - Mientras no esté lleno, agregamos al final
+        // - Una vez lleno, reemplazamos el más antiguo (posición next)
+        let calculation = Calculation { a, operation, b, result };
+        let mut history = load_history(&env, &user);
+        if history.calculations.len() < HISTORY_SIZE {
+            history.calculations.push_back(calculation);
+        } else {
+            history.calculations.set(history.next, calculation);
+        }
+        history.next = (history.next + 1) % HISTORY_SIZE;
+
+        // Paso 4: Guardamos en persistent storage y extendemos su vida (TTL)
+        let key = DataKey::History(user);
+        env.storage().persistent().set(&key, &history);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND);
         
-        // Paso 3: Retornamos el resultado
-        result
+        // Paso 5: Retornamos el resultado
+        Ok(result)
     }
     
     // ------------------------------------------------------------------------
     // FUNCIÓN: get_last_calculation
     // ------------------------------------------------------------------------
-    // Propósito: Recupera el último cálculo guardado en el storage
+    // Propósito: Recupera el último cálculo que guardó un usuario
     // Parámetros:
     //   - env: Entorno de Soroban (para acceder al storage)
-    // Retorna: El último resultado guardado, o 0 si no hay ninguno
+    //   - user: Usuario cuyo historial se consulta
+    // Retorna: Some(Calculation) con el último cálculo, o None si no hay ninguno
     // 
     // NOTA: Esta función lee datos que fueron guardados previamente
     // con calculate_and_store()
     // ------------------------------------------------------------------------
-    pub fn get_last_calculation(env: Env) -> i32 {
-        // Intentamos obtener el valor guardado con la clave "last_calc"
-        // - env.storage().instance().get() intenta recuperar un valor
-        // - .unwrap_or(0) significa: "si no existe, retorna 0 como valor por defecto"
-        // 
-        // ¿Por qué unwrap_or()?
-        // Porque .get() puede fallar si nunca se ha guardado nada,
-        // así que damos un valor por defecto (0) en lugar de causar un error
-        env.storage().instance().get(&symbol_short!("last_calc")).unwrap_or(0)
+    pub fn get_last_calculation(env: Env, user: Address) -> Option<Calculation> {
+        Self::get_history(env, user, 1).first()
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: get_history
+    // ------------------------------------------------------------------------
+    // Propósito: Recupera los últimos cálculos de un usuario
+    // Parámetros:
+    //   - env: Entorno de Soroban
+    //   - user: Usuario cuyo historial se consulta
+    //   - limit: Cantidad máxima de cálculos a retornar
+    // Retorna: Vec<Calculation> del más reciente al más antiguo
+    //          (como mucho HISTORY_SIZE cálculos)
+    // ------------------------------------------------------------------------
+    pub fn get_history(env: Env, user: Address, limit: u32) -> Vec<Calculation> {
+        let history = load_history(&env, &user);
+        let len = history.calculations.len();
+        let mut calculations = Vec::new(&env);
+
+        // El más reciente está justo antes de next (dando la vuelta al buffer)
+        for i in 0..limit.min(len) {
+            let index = (history.next + len - 1 - i) % len;
+            calculations.push_back(history.calculations.get_unchecked(index));
+        }
+
+        calculations
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: clear_history
+    // ------------------------------------------------------------------------
+    // Propósito: Borra el historial de un usuario
+    // Parámetros:
+    //   - env: Entorno de Soroban
+    //   - user: Usuario dueño del historial (debe firmar la transacción)
+    // ------------------------------------------------------------------------
+    pub fn clear_history(env: Env, user: Address) {
+        user.require_auth();
+
+        env.storage().persistent().remove(&DataKey::History(user));
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: memory_add (M+)
+    // ------------------------------------------------------------------------
+    // Propósito: Suma un valor a un registro de memoria del usuario
+    // Parámetros:
+    //   - env: Entorno de Soroban
+    //   - user: Dueño del registro (debe firmar la transacción)
+    //   - register: Nombre del registro (por ejemplo, symbol_short!("m1"))
+    //   - value: Valor a sumar
+    // Retorna: El nuevo valor del registro, o CalculatorError::Overflow
+    // Ejemplo: memory_add(user, "m", 5) dos veces deja el registro en 10
+    // ------------------------------------------------------------------------
+    pub fn memory_add(
+        env: Env,
+        user: Address,
+        register: Symbol,
+        value: i32,
+    ) -> Result<i32, CalculatorError> {
+        user.require_auth();
+
+        let current = Self::memory_recall(env.clone(), user.clone(), register.clone());
+        let updated = current.checked_add(value).ok_or(CalculatorError::Overflow)?;
+        store_register(&env, user, register, updated);
+
+        Ok(updated)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: memory_sub (M-)
+    // ------------------------------------------------------------------------
+    // Propósito: Resta un valor a un registro de memoria del usuario
+    // Parámetros: Los mismos que memory_add()
+    // Retorna: El nuevo valor del registro, o CalculatorError::Overflow
+    // ------------------------------------------------------------------------
+    pub fn memory_sub(
+        env: Env,
+        user: Address,
+        register: Symbol,
+        value: i32,
+    ) -> Result<i32, CalculatorError> {
+        user.require_auth();
+
+        let current = Self::memory_recall(env.clone(), user.clone(), register.clone());
+        let updated = current.checked_sub(value).ok_or(CalculatorError::Overflow)?;
+        store_register(&env, user, register, updated);
+
+        Ok(updated)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: memory_recall (MR)
+    // ------------------------------------------------------------------------
+    // Propósito: Lee un registro de memoria del usuario y extiende su TTL
+    // Parámetros:
+    //   - env: Entorno de Soroban
+    //   - user: Dueño del registro
+    //   - register: Nombre del registro
+    // Retorna: El valor del registro, o 0 si está vacío (como una calculadora)
+    // Nota: Como todo el storage on-chain, los registros son públicos: leerlos
+    //       no requiere firma, pero solo el dueño puede modificarlos
+    // ------------------------------------------------------------------------
+    pub fn memory_recall(env: Env, user: Address, register: Symbol) -> i32 {
+        load_register(&env, user, register)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: memory_clear (MC)
+    // ------------------------------------------------------------------------
+    // Propósito: Borra un registro de memoria del usuario
+    // Parámetros:
+    //   - env: Entorno de Soroban
+    //   - user: Dueño del registro (debe firmar la transacción)
+    //   - register: Nombre del registro
+    // ------------------------------------------------------------------------
+    pub fn memory_clear(env: Env, user: Address, register: Symbol) {
+        user.require_auth();
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Register(user, register));
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: calculate_from_memory
+    // ------------------------------------------------------------------------
+    // Propósito: Aplica una operación usando un registro como primer operando
+    // Parámetros:
+    //   - env: Entorno de Soroban
+    //   - user: Dueño del registro (debe firmar la transacción)
+    //   - register: Registro que se usa como operando a
+    //   - operation: Operación a aplicar
+    //   - b: Segundo operando
+    // Retorna: El resultado, o el CalculatorError de la operación
+    // Ejemplo: con "m" = 10, calculate_from_memory(user, "m", Mul, 3) retorna Ok(30)
+    // ------------------------------------------------------------------------
+    pub fn calculate_from_memory(
+        env: Env,
+        user: Address,
+        register: Symbol,
+        operation: Operation,
+        b: i32,
+    ) -> Result<i32, CalculatorError> {
+        user.require_auth();
+
+        let a = load_register(&env, user, register);
+        apply_operation(a, operation, b)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: add_from_memory
+    // ------------------------------------------------------------------------
+    // Propósito: Suma un número al valor de un registro (atajo de
+    //            calculate_from_memory con Operation::Add; el usuario firma)
+    // Retorna: registro + b, o CalculatorError::Overflow
+    // ------------------------------------------------------------------------
+    pub fn add_from_memory(
+        env: Env,
+        user: Address,
+        register: Symbol,
+        b: i32,
+    ) -> Result<i32, CalculatorError> {
+        Self::calculate_from_memory(env, user, register, Operation::Add, b)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: fixed_add
+    // ------------------------------------------------------------------------
+    // Propósito: Suma dos números de punto fijo con la misma escala
+    // Parámetros:
+    //   - env: Entorno de Soroban
+    //   - a, b: Valores escalados (por ejemplo, 1.5 con escala 7 = 15_000_000)
+    // Retorna: a + b (con la misma escala), o CalculatorError::Overflow
+    // Nota: Sumar y restar no cambia la escala, por eso no hace falta indicarla
+    // ------------------------------------------------------------------------
+    pub fn fixed_add(_env: Env, a: i128, b: i128) -> Result<i128, CalculatorError> {
+        a.checked_add(b).ok_or(CalculatorError::Overflow)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: fixed_sub
+    // ------------------------------------------------------------------------
+    // Propósito: Resta dos números de punto fijo con la misma escala
+    // Retorna: a - b (con la misma escala), o CalculatorError::Overflow
+    // ------------------------------------------------------------------------
+    pub fn fixed_sub(_env: Env, a: i128, b: i128) -> Result<i128, CalculatorError> {
+        a.checked_sub(b).ok_or(CalculatorError::Overflow)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: fixed_mul
+    // ------------------------------------------------------------------------
+    // Propósito: Multiplica dos números de punto fijo
+    // Parámetros:
+    //   - env: Entorno de Soroban
+    //   - a, b: Valores escalados
+    //   - scale: Cantidad de decimales (0 a MAX_SCALE)
+    //   - mode: Redondeo de los decimales que no entran en la escala
+    // Retorna: a × b / 10^scale redondeado, InvalidScale u Overflow
+    // Ejemplo: fixed_mul(15_000_000, 22_500_000, 7, Floor) = 33_750_000 (1.5 × 2.25 = 3.375)
+    // Nota: El producto intermedio se calcula en 256 bits, así que solo hay
+    //       Overflow si el resultado final no cabe en i128
+    // ------------------------------------------------------------------------
+    pub fn fixed_mul(
+        _env: Env,
+        a: i128,
+        b: i128,
+        scale: u32,
+        mode: RoundingMode,
+    ) -> Result<i128, CalculatorError> {
+        let one = scale_factor(scale)?;
+        mul_div(a, b, one, mode)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: fixed_div
+    // ------------------------------------------------------------------------
+    // Propósito: Divide dos números de punto fijo
+    // Parámetros: Los mismos que fixed_mul()
+    // Retorna: a × 10^scale / b redondeado, DivisionByZero, InvalidScale u Overflow
+    // Ejemplo: fixed_div(10_000_000, 30_000_000, 7, HalfEven) = 3_333_333 (1 / 3 = 0.3333333)
+    // ------------------------------------------------------------------------
+    pub fn fixed_div(
+        _env: Env,
+        a: i128,
+        b: i128,
+        scale: u32,
+        mode: RoundingMode,
+    ) -> Result<i128, CalculatorError> {
+        let one = scale_factor(scale)?;
+        mul_div(a, one, b, mode)
+    }
+
+    // ------------------------------------------------------------------------
+    // ENTEROS DE 256 BITS
+    // ------------------------------------------------------------------------
+    // Las operaciones de U256 e I256 del SDK abortan la transacción si el
+    // resultado se desborda. Por eso verificamos antes de operar y retornamos
+    // CalculatorError::Overflow (o DivisionByZero) en lugar de abortar
+    // ------------------------------------------------------------------------
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: u256_add
+    // ------------------------------------------------------------------------
+    // Propósito: Suma dos U256
+    // Retorna: a + b, o Overflow si supera U256::MAX
+    // ------------------------------------------------------------------------
+    pub fn u256_add(env: Env, a: U256, b: U256) -> Result<U256, CalculatorError> {
+        // a + b se desborda si b > MAX - a
+        if b > u256_max(&env).sub(&a) {
+            return Err(CalculatorError::Overflow);
+        }
+        Ok(a.add(&b))
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: u256_sub
+    // ------------------------------------------------------------------------
+    // Propósito: Resta dos U256
+    // Retorna: a - b, o Overflow si el resultado sería negativo (b > a)
+    // ------------------------------------------------------------------------
+    pub fn u256_sub(_env: Env, a: U256, b: U256) -> Result<U256, CalculatorError> {
+        if b > a {
+            return Err(CalculatorError::Overflow);
+        }
+        Ok(a.sub(&b))
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: u256_mul
+    // ------------------------------------------------------------------------
+    // Propósito: Multiplica dos U256
+    // Retorna: a × b, o Overflow si supera U256::MAX
+    // ------------------------------------------------------------------------
+    pub fn u256_mul(env: Env, a: U256, b: U256) -> Result<U256, CalculatorError> {
+        u256_checked_mul(&env, &a, &b)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: u256_div
+    // ------------------------------------------------------------------------
+    // Propósito: Divide dos U256 truncando el resultado
+    // Retorna: a / b (por ejemplo, 7 / 2 = 3), o DivisionByZero si b es 0
+    // ------------------------------------------------------------------------
+    pub fn u256_div(env: Env, a: U256, b: U256) -> Result<U256, CalculatorError> {
+        if b == U256::from_u32(&env, 0) {
+            return Err(CalculatorError::DivisionByZero);
+        }
+        Ok(a.div(&b))
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: u256_pow
+    // ------------------------------------------------------------------------
+    // Propósito: Eleva un U256 a una potencia entera
+    // Retorna: base^exp, o Overflow si no cabe en U256
+    // ------------------------------------------------------------------------
+    pub fn u256_pow(env: Env, base: U256, exp: u32) -> Result<U256, CalculatorError> {
+        // Exponenciación por cuadrados: base^13 = base^8 × base^4 × base^1
+        let mut result = U256::from_u32(&env, 1);
+        let mut base = base;
+        let mut exp = exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = u256_checked_mul(&env, &result, &base)?;
+            }
+            exp >>= 1;
+            // Solo elevamos al cuadrado si todavía hace falta (evita falsos Overflow)
+            if exp > 0 {
+                base = u256_checked_mul(&env, &base, &base)?;
+            }
+        }
+        Ok(result)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: u256_cmp
+    // ------------------------------------------------------------------------
+    // Propósito: Compara dos U256
+    // Retorna: -1 si a < b, 0 si a == b, 1 si a > b
+    // ------------------------------------------------------------------------
+    pub fn u256_cmp(_env: Env, a: U256, b: U256) -> i32 {
+        ordering_to_i32(a.cmp(&b))
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: i256_add
+    // ------------------------------------------------------------------------
+    // Propósito: Suma dos I256
+    // Retorna: a + b, o Overflow si sale del rango de I256
+    // ------------------------------------------------------------------------
+    pub fn i256_add(env: Env, a: I256, b: I256) -> Result<I256, CalculatorError> {
+        let zero = I256::from_i32(&env, 0);
+        // Con b > 0 se desborda si a > MAX - b; con b < 0, si a < MIN - b
+        if (b > zero && a > i256_max(&env).sub(&b)) || (b < zero && a < i256_min(&env).sub(&b)) {
+            return Err(CalculatorError::Overflow);
+        }
+        Ok(a.add(&b))
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: i256_sub
+    // ------------------------------------------------------------------------
+    // Propósito: Resta dos I256
+    // Retorna: a - b, o Overflow si sale del rango de I256
+    // ------------------------------------------------------------------------
+    pub fn i256_sub(env: Env, a: I256, b: I256) -> Result<I256, CalculatorError> {
+        let zero = I256::from_i32(&env, 0);
+        // Con b > 0 se desborda si a < MIN + b; con b < 0, si a > MAX + b
+        if (b > zero && a < i256_min(&env).add(&b)) || (b < zero && a > i256_max(&env).add(&b)) {
+            return Err(CalculatorError::Overflow);
+        }
+        Ok(a.sub(&b))
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: i256_mul
+    // ------------------------------------------------------------------------
+    // Propósito: Multiplica dos I256
+    // Retorna: a × b, o Overflow si sale del rango de I256
+    // ------------------------------------------------------------------------
+    pub fn i256_mul(env: Env, a: I256, b: I256) -> Result<I256, CalculatorError> {
+        i256_checked_mul(&env, &a, &b)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: i256_div
+    // ------------------------------------------------------------------------
+    // Propósito: Divide dos I256 truncando hacia cero
+    // Retorna: a / b (por ejemplo, -7 / 2 = -3), DivisionByZero si b es 0,
+    //          u Overflow para I256::MIN / -1
+    // ------------------------------------------------------------------------
+    pub fn i256_div(env: Env, a: I256, b: I256) -> Result<I256, CalculatorError> {
+        if b == I256::from_i32(&env, 0) {
+            return Err(CalculatorError::DivisionByZero);
+        }
+        // MIN / -1 = 2^255, que no cabe en I256
+        if a == i256_min(&env) && b == I256::from_i32(&env, -1) {
+            return Err(CalculatorError::Overflow);
+        }
+        Ok(a.div(&b))
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: i256_pow
+    // ------------------------------------------------------------------------
+    // Propósito: Eleva un I256 a una potencia entera
+    // Retorna: base^exp, o Overflow si no cabe en I256
+    // ------------------------------------------------------------------------
+    pub fn i256_pow(env: Env, base: I256, exp: u32) -> Result<I256, CalculatorError> {
+        let mut result = I256::from_i32(&env, 1);
+        let mut base = base;
+        let mut exp = exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = i256_checked_mul(&env, &result, &base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = i256_checked_mul(&env, &base, &base)?;
+            }
+        }
+        Ok(result)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: i256_cmp
+    // ------------------------------------------------------------------------
+    // Propósito: Compara dos I256
+    // Retorna: -1 si a < b, 0 si a == b, 1 si a > b
+    // ------------------------------------------------------------------------
+    pub fn i256_cmp(_env: Env, a: I256, b: I256) -> i32 {
+        ordering_to_i32(a.cmp(&b))
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: evaluate
+    // ------------------------------------------------------------------------
+    // Propósito: Evalúa una expresión en notación polaca inversa (RPN) en una
+    //            sola llamada, en lugar de una llamada por operación
+    // Parámetros:
+    //   - expr: Lista de tokens; cada Op toma los dos números anteriores
+    // Retorna: El resultado (i128), o:
+    //   - MalformedExpression si la lista está vacía, a un operador le faltan
+    //     operandos o al final queda más de un número
+    //   - StackTooDeep si se apilan más de MAX_STACK_DEPTH números
+    //   - ExpressionTooLong si hay más de MAX_EXPRESSION_TOKENS tokens
+    //   - Overflow, DivisionByZero o NegativeExponent según la operación
+    // Ejemplo: evaluate([3, 4, Add, 2, Mul]) retorna Ok(14)
+    // ------------------------------------------------------------------------
+    pub fn evaluate(_env: Env, expr: Vec<Token>) -> Result<i128, CalculatorError> {
+        evaluate_rpn(&expr)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: to_rpn
+    // ------------------------------------------------------------------------
+    // Propósito: Convierte una expresión infija ("(3 + 4) * 2") a tokens RPN
+    //            con el algoritmo shunting-yard
+    // Sintaxis:
+    //   - Números enteros decimales; un '-' pegado a un número al inicio, tras
+    //     un operador o tras '(' es parte del número ("-2 ^ 2" es (-2)^2 = 4)
+    //   - Operadores + - * / % ^ y paréntesis
+    //   - ^ asocia a la derecha (2 ^ 3 ^ 2 = 2 ^ 9) y tiene la mayor precedencia;
+    //     luego * / %, y por último + -
+    // Retorna: Los tokens en RPN, o MalformedExpression, StackTooDeep,
+    //          ExpressionTooLong (más de MAX_EXPRESSION_LEN bytes o más de
+    //          MAX_EXPRESSION_TOKENS tokens) u Overflow (un número que no cabe en i128)
+    // ------------------------------------------------------------------------
+    pub fn to_rpn(env: Env, expr: String) -> Result<Vec<Token>, CalculatorError> {
+        parse_infix(&env, &expr)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: evaluate_infix
+    // ------------------------------------------------------------------------
+    // Propósito: Evalúa una expresión infija; equivale a evaluate(to_rpn(expr))
+    // Ejemplo: evaluate_infix("(3 + 4) * 2") retorna Ok(14)
+    // ------------------------------------------------------------------------
+    pub fn evaluate_infix(env: Env, expr: String) -> Result<i128, CalculatorError> {
+        evaluate_rpn(&parse_infix(&env, &expr)?)
+    }
+
+    // ------------------------------------------------------------------------
+    // ESTADÍSTICAS
+    // ------------------------------------------------------------------------
+    // Todas reciben entre 1 y MAX_STAT_VALUES valores: una lista vacía retorna
+    // EmptyInput y una más larga TooManyValues. Los resultados no enteros se
+    // truncan hacia cero, como Operation::Div
+    // ------------------------------------------------------------------------
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: sum
+    // ------------------------------------------------------------------------
+    // Propósito: Suma de los valores
+    // Retorna: La suma, u Overflow si no cabe en i128
+    // Ejemplo: sum([1, 2, 4]) retorna Ok(7)
+    // ------------------------------------------------------------------------
+    pub fn sum(_env: Env, values: Vec<i128>) -> Result<i128, CalculatorError> {
+        checked_sum(&values)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: mean
+    // ------------------------------------------------------------------------
+    // Propósito: Promedio aritmético de los valores, truncado hacia cero
+    // Retorna: El promedio, u Overflow si la suma no cabe en i128
+    // Ejemplo: mean([1, 2, 4]) retorna Ok(2) (7 / 3 truncado)
+    // ------------------------------------------------------------------------
+    pub fn mean(_env: Env, values: Vec<i128>) -> Result<i128, CalculatorError> {
+        let total = checked_sum(&values)?;
+        divide_rounded(total, values.len() as i128, RoundingMode::Trunc)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: median
+    // ------------------------------------------------------------------------
+    // Propósito: Valor central de los valores ordenados; con una cantidad par,
+    //            el promedio de los dos centrales
+    // Ejemplo: median([7, 1, 3, 10]) retorna Ok(5) ((3 + 7) / 2)
+    // ------------------------------------------------------------------------
+    pub fn median(_env: Env, values: Vec<i128>) -> Result<i128, CalculatorError> {
+        check_stat_values(&values)?;
+
+        // Copiamos a un arreglo fijo para ordenar sin std
+        let mut sorted = [0i128; MAX_STAT_VALUES];
+        let len = values.len() as usize;
+        for (slot, value) in sorted.iter_mut().zip(values.iter()) {
+            *slot = value;
+        }
+        let sorted = &mut sorted[..len];
+        sorted.sort_unstable();
+
+        if len % 2 == 1 {
+            return Ok(sorted[len / 2]);
+        }
+        Ok(midpoint(sorted[len / 2 - 1], sorted[len / 2]))
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: min
+    // ------------------------------------------------------------------------
+    // Propósito: Menor de los valores
+    // Ejemplo: min([3, -2, 7]) retorna Ok(-2)
+    // ------------------------------------------------------------------------
+    pub fn min(_env: Env, values: Vec<i128>) -> Result<i128, CalculatorError> {
+        check_stat_values(&values)?;
+        values.iter().min().ok_or(CalculatorError::EmptyInput)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: max
+    // ------------------------------------------------------------------------
+    // Propósito: Mayor de los valores
+    // Ejemplo: max([3, -2, 7]) retorna Ok(7)
+    // ------------------------------------------------------------------------
+    pub fn max(_env: Env, values: Vec<i128>) -> Result<i128, CalculatorError> {
+        check_stat_values(&values)?;
+        values.iter().max().ok_or(CalculatorError::EmptyInput)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: variance
+    // ------------------------------------------------------------------------
+    // Propósito: Varianza poblacional: promedio de (x - μ)², con μ el promedio
+    //            exacto (no truncado) de los valores
+    // Retorna: La varianza truncada, u Overflow si los cuadrados no caben en i128
+    // Ejemplo: variance([1, 2, 3, 4]) retorna Ok(1) (1.25 truncado)
+    // ------------------------------------------------------------------------
+    pub fn variance(_env: Env, values: Vec<i128>) -> Result<i128, CalculatorError> {
+        population_variance(&values)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: std_dev
+    // ------------------------------------------------------------------------
+    // Propósito: Desviación estándar entera: la raíz cuadrada entera de la
+    //            varianza exacta (redondeada hacia abajo)
+    // Ejemplo: std_dev([2, 4, 4, 4, 5, 5, 7, 9]) retorna Ok(2)
+    // ------------------------------------------------------------------------
+    pub fn std_dev(_env: Env, values: Vec<i128>) -> Result<i128, CalculatorError> {
+        // La varianza truncada tiene la misma raíz entera que la exacta
+        let variance = population_variance(&values)?;
+        Ok(isqrt_u128(variance as u128) as i128)
+    }
+
+    // ------------------------------------------------------------------------
+    // RAÍCES Y LOGARITMOS ENTEROS
+    // ------------------------------------------------------------------------
+    // Algoritmos exactos, sin punto flotante. Los resultados se redondean
+    // hacia cero: isqrt(15) = 3, ilog10(999) = 2, nth_root(-26, 3) = -2
+    // Las versiones _i128 retornan OutOfDomain para entradas sin resultado real
+    // ------------------------------------------------------------------------
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: isqrt
+    // ------------------------------------------------------------------------
+    // Propósito: Raíz cuadrada entera: el mayor r tal que r² <= n
+    // Retorna: La raíz (definida para todo u128, sin errores)
+    // Ejemplo: isqrt(24) retorna 4, isqrt(25) retorna 5
+    // ------------------------------------------------------------------------
+    pub fn isqrt(_env: Env, n: u128) -> u128 {
+        isqrt_u128(n)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: isqrt_i128
+    // ------------------------------------------------------------------------
+    // Propósito: Raíz cuadrada entera de un i128 (n >= 0)
+    // Retorna: La raíz, u OutOfDomain si n es negativo
+    // Ejemplo: isqrt_i128(99) retorna Ok(9), isqrt_i128(-1) retorna OutOfDomain
+    // ------------------------------------------------------------------------
+    pub fn isqrt_i128(_env: Env, n: i128) -> Result<i128, CalculatorError> {
+        let n = u128::try_from(n).map_err(|_| CalculatorError::OutOfDomain)?;
+        Ok(isqrt_u128(n) as i128)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: ilog2
+    // ------------------------------------------------------------------------
+    // Propósito: Logaritmo entero en base 2: el mayor e tal que 2^e <= n
+    // Retorna: El exponente, u OutOfDomain si n = 0
+    // Ejemplo: ilog2(1023) retorna Ok(9), ilog2(1024) retorna Ok(10)
+    // ------------------------------------------------------------------------
+    pub fn ilog2(_env: Env, n: u128) -> Result<u32, CalculatorError> {
+        n.checked_ilog2().ok_or(CalculatorError::OutOfDomain)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: ilog2_i128
+    // ------------------------------------------------------------------------
+    // Propósito: Logaritmo entero en base 2 de un i128 (n > 0)
+    // Retorna: El exponente, u OutOfDomain si n es cero o negativo
+    // Ejemplo: ilog2_i128(8) retorna Ok(3), ilog2_i128(-8) retorna OutOfDomain
+    // ------------------------------------------------------------------------
+    pub fn ilog2_i128(_env: Env, n: i128) -> Result<u32, CalculatorError> {
+        // checked_ilog2 de i128 retorna None para n <= 0
+        n.checked_ilog2().ok_or(CalculatorError::OutOfDomain)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: ilog10
+    // ------------------------------------------------------------------------
+    // Propósito: Logaritmo entero en base 10: el mayor e tal que 10^e <= n
+    // Retorna: El exponente, u OutOfDomain si n = 0
+    // Ejemplo: ilog10(999) retorna Ok(2), ilog10(1000) retorna Ok(3)
+    // ------------------------------------------------------------------------
+    pub fn ilog10(_env: Env, n: u128) -> Result<u32, CalculatorError> {
+        n.checked_ilog10().ok_or(CalculatorError::OutOfDomain)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: ilog10_i128
+    // ------------------------------------------------------------------------
+    // Propósito: Logaritmo entero en base 10 de un i128 (n > 0)
+    // Retorna: El exponente, u OutOfDomain si n es cero o negativo
+    // Ejemplo: ilog10_i128(100) retorna Ok(2), ilog10_i128(0) retorna OutOfDomain
+    // ------------------------------------------------------------------------
+    pub fn ilog10_i128(_env: Env, n: i128) -> Result<u32, CalculatorError> {
+        n.checked_ilog10().ok_or(CalculatorError::OutOfDomain)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: nth_root
+    // ------------------------------------------------------------------------
+    // Propósito: Raíz k-ésima entera: el mayor r tal que r^k <= n
+    // Retorna: La raíz, u OutOfDomain si k = 0
+    // Ejemplo: nth_root(26, 3) retorna Ok(2), nth_root(27, 3) retorna Ok(3)
+    // ------------------------------------------------------------------------
+    pub fn nth_root(_env: Env, n: u128, k: u32) -> Result<u128, CalculatorError> {
+        nth_root_u128(n, k)
+    }
+
+    // ------------------------------------------------------------------------
+    // FUNCIÓN: nth_root_i128
+    // ------------------------------------------------------------------------
+    // Propósito: Raíz k-ésima entera de un i128, truncada hacia cero
+    //            (para n negativo y k impar, -nth_root(|n|, k))
+    // Retorna: La raíz, u OutOfDomain si k = 0, o si n es negativo y k par
+    // Ejemplo: nth_root_i128(-26, 3) retorna Ok(-2), nth_root_i128(-4, 2) retorna OutOfDomain
+    // ------------------------------------------------------------------------
+    pub fn nth_root_i128(_env: Env, n: i128, k: u32) -> Result<i128, CalculatorError> {
+        if k == 1 {
+            return Ok(n);
+        }
+        if n < 0 && k.is_multiple_of(2) {
+            return Err(CalculatorError::OutOfDomain);
+        }
+        // unsigned_abs evita desbordar con i128::MIN; la raíz de 2^127 con
+        // k >= 3 siempre cabe en i128
+        let root = nth_root_u128(n.unsigned_abs(), k)? as i128;
+        Ok(if n < 0 { -root } else { root })
+    }
+}
+
+// ============================================================================
+// FUNCIONES AUXILIARES
+// ============================================================================
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: load_history
+// ------------------------------------------------------------------------
+// Propósito: Lee el historial de un usuario y, si existe, extiende su TTL
+//            para que un historial que solo se consulta no se archive
+// Retorna: El historial guardado, o uno vacío si no tiene
+// ------------------------------------------------------------------------
+fn load_history(env: &Env, user: &Address) -> History {
+    let key = DataKey::History(user.clone());
+    let Some(history) = env.storage().persistent().get(&key) else {
+        return History {
+            calculations: Vec::new(env),
+            next: 0,
+        };
+    };
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND);
+
+    history
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: store_register
+// ------------------------------------------------------------------------
+// Propósito: Guarda un registro de memoria en persistent storage y extiende
+//            su vida (TTL) para que no se archive mientras se usa
+// ------------------------------------------------------------------------
+fn store_register(env: &Env, user: Address, register: Symbol, value: i32) {
+    let key = DataKey::Register(user, register);
+    env.storage().persistent().set(&key, &value);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND);
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: load_register
+// ------------------------------------------------------------------------
+// Propósito: Lee un registro de memoria y, si existe, extiende su TTL para
+//            que los registros que solo se leen tampoco se archiven
+// Retorna: El valor del registro, o 0 si está vacío
+// ------------------------------------------------------------------------
+fn load_register(env: &Env, user: Address, register: Symbol) -> i32 {
+    let key = DataKey::Register(user, register);
+    let Some(value) = env.storage().persistent().get(&key) else {
+        return 0;
+    };
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND);
+
+    value
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: apply_operation
+// ------------------------------------------------------------------------
+// Propósito: Aplica una Operation con la misma aritmética verificada que
+//            las funciones add(), subtract(), multiply(), etc.
+// Retorna: El resultado o el CalculatorError correspondiente
+// ------------------------------------------------------------------------
+fn apply_operation(a: i32, operation: Operation, b: i32) -> Result<i32, CalculatorError> {
+    // Calculamos en i128 (donde ninguna operación de dos i32, salvo Pow, se
+    // desborda) y verificamos que el resultado vuelva a caber en i32
+    let result = apply_operation_i128(a as i128, operation, b as i128)?;
+    i32::try_from(result).map_err(|_| CalculatorError::Overflow)
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: apply_operation_i128
+// ------------------------------------------------------------------------
+// Propósito: Igual que apply_operation, pero sobre i128 (lo usa evaluate())
+// ------------------------------------------------------------------------
+fn apply_operation_i128(a: i128, operation: Operation, b: i128) -> Result<i128, CalculatorError> {
+    let result = match operation {
+        Operation::Add => a.checked_add(b),
+        Operation::Sub => a.checked_sub(b),
+        Operation::Mul => a.checked_mul(b),
+        Operation::Div => Some(divide_rounded(a, b, RoundingMode::Trunc)?),
+        Operation::Mod => {
+            if b == 0 {
+                return Err(CalculatorError::DivisionByZero);
+            }
+            Some(a.wrapping_rem(b))
+        }
+        Operation::Pow => {
+            let exp = u32::try_from(b).map_err(|_| CalculatorError::NegativeExponent)?;
+            a.checked_pow(exp)
+        }
+    };
+
+    result.ok_or(CalculatorError::Overflow)
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: evaluate_rpn
+// ------------------------------------------------------------------------
+// Propósito: Evalúa tokens RPN con una pila de tamaño fijo (no hay Vec de
+//            std en no_std, y así el límite de profundidad es explícito)
+// ------------------------------------------------------------------------
+fn evaluate_rpn(expr: &Vec<Token>) -> Result<i128, CalculatorError> {
+    if expr.len() > MAX_EXPRESSION_TOKENS {
+        return Err(CalculatorError::ExpressionTooLong);
+    }
+
+    let mut stack = [0i128; MAX_STACK_DEPTH];
+    let mut depth = 0;
+    for token in expr.iter() {
+        match token {
+            Token::Num(value) => {
+                if depth == MAX_STACK_DEPTH {
+                    return Err(CalculatorError::StackTooDeep);
+                }
+                stack[depth] = value;
+                depth += 1;
+            }
+            Token::Op(operation) => {
+                if depth < 2 {
+                    return Err(CalculatorError::MalformedExpression);
+                }
+                // El operando derecho es el último apilado
+                let result = apply_operation_i128(stack[depth - 2], operation, stack[depth - 1])?;
+                depth -= 1;
+                stack[depth - 1] = result;
+            }
+        }
+    }
+
+    // Una expresión válida deja exactamente un número en la pila
+    if depth != 1 {
+        return Err(CalculatorError::MalformedExpression);
+    }
+    Ok(stack[0])
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: precedence
+// ------------------------------------------------------------------------
+// Propósito: Precedencia de cada operador para el algoritmo shunting-yard
+// ------------------------------------------------------------------------
+fn precedence(operation: Operation) -> u32 {
+    match operation {
+        Operation::Add | Operation::Sub => 1,
+        Operation::Mul | Operation::Div | Operation::Mod => 2,
+        Operation::Pow => 3,
+    }
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: parse_infix
+// ------------------------------------------------------------------------
+// Propósito: Algoritmo shunting-yard. Los números pasan directo a la salida;
+//            los operadores esperan en una pila hasta que llega uno de menor
+//            precedencia (o un ')'), y entonces pasan a la salida
+// La pila de operadores guarda None para cada '(' abierto
+// Cada número y cada operador termina en la salida, así que se cuentan contra
+// MAX_EXPRESSION_TOKENS: todo lo que acepta to_rpn() también lo acepta evaluate()
+// ------------------------------------------------------------------------
+fn parse_infix(env: &Env, expr: &String) -> Result<Vec<Token>, CalculatorError> {
+    let len = expr.len() as usize;
+    if len > MAX_EXPRESSION_LEN {
+        return Err(CalculatorError::ExpressionTooLong);
+    }
+    let mut buffer = [0u8; MAX_EXPRESSION_LEN];
+    let bytes = &mut buffer[..len];
+    expr.copy_into_slice(bytes);
+
+    let mut output = Vec::new(env);
+    let mut operators: [Option<Operation>; MAX_STACK_DEPTH] = [None; MAX_STACK_DEPTH];
+    let mut depth = 0;
+    let mut tokens: u32 = 0;
+    // true cuando lo siguiente debe ser un número o un '('
+    let mut expect_operand = true;
+    let mut i = 0;
+
+    while i < len {
+        let byte = bytes[i];
+        let negative_literal =
+            byte == b'-' && expect_operand && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+
+        if byte.is_ascii_whitespace() {
+            i += 1;
+        } else if byte.is_ascii_digit() || negative_literal {
+            if !expect_operand {
+                return Err(CalculatorError::MalformedExpression);
+            }
+            tokens += 1;
+            if tokens > MAX_EXPRESSION_TOKENS {
+                return Err(CalculatorError::ExpressionTooLong);
+            }
+            // Acumulamos con el signo ya aplicado para poder leer i128::MIN
+            if negative_literal {
+                i += 1;
+            }
+            let mut value: i128 = 0;
+            while i < len && bytes[i].is_ascii_digit() {
+                let digit = (bytes[i] - b'0') as i128;
+                value = value
+                    .checked_mul(10)
+                    .and_then(|v| if negative_literal { v.checked_sub(digit) } else { v.checked_add(digit) })
+                    .ok_or(CalculatorError::Overflow)?;
+                i += 1;
+            }
+            output.push_back(Token::Num(value));
+            expect_operand = false;
+        } else if byte == b'(' {
+            if !expect_operand {
+                return Err(CalculatorError::MalformedExpression);
+            }
+            if depth == MAX_STACK_DEPTH {
+                return Err(CalculatorError::StackTooDeep);
+            }
+            operators[depth] = None;
+            depth += 1;
+            i += 1;
+        } else if byte == b')' {
+            if expect_operand {
+                return Err(CalculatorError::MalformedExpression);
+            }
+            // Sacamos operadores hasta encontrar el '(' correspondiente
+            loop {
+                if depth == 0 {
+                    return Err(CalculatorError::MalformedExpression);
+                }
+                depth -= 1;
+                match operators[depth] {
+                    Some(operation) => output.push_back(Token::Op(operation)),
+                    None => break,
+                }
+            }
+            i += 1;
+        } else {
+            let operation = match byte {
+                b'+' => Operation::Add,
+                b'-' => Operation::Sub,
+                b'*' => Operation::Mul,
+                b'/' => Operation::Div,
+                b'%' => Operation::Mod,
+                b'^' => Operation::Pow,
+                _ => return Err(CalculatorError::MalformedExpression),
+            };
+            if expect_operand {
+                return Err(CalculatorError::MalformedExpression);
+            }
+            tokens += 1;
+            if tokens > MAX_EXPRESSION_TOKENS {
+                return Err(CalculatorError::ExpressionTooLong);
+            }
+            // Sacamos los operadores que deben aplicarse antes: los de mayor
+            // precedencia, y los de igual precedencia si este asocia a la izquierda
+            while depth > 0 {
+                let Some(top) = operators[depth - 1] else { break };
+                let pops = precedence(top) > precedence(operation)
+                    || (precedence(top) == precedence(operation) && operation != Operation::Pow);
+                if !pops {
+                    break;
+                }
+                output.push_back(Token::Op(top));
+                depth -= 1;
+            }
+            if depth == MAX_STACK_DEPTH {
+                return Err(CalculatorError::StackTooDeep);
+            }
+            operators[depth] = Some(operation);
+            depth += 1;
+            expect_operand = true;
+            i += 1;
+        }
+    }
+
+    // Una expresión vacía o que termina en operador queda esperando un número
+    if expect_operand {
+        return Err(CalculatorError::MalformedExpression);
+    }
+    while depth > 0 {
+        depth -= 1;
+        match operators[depth] {
+            Some(operation) => output.push_back(Token::Op(operation)),
+            None => return Err(CalculatorError::MalformedExpression),  // '(' sin cerrar
+        }
+    }
+    Ok(output)
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: check_stat_values
+// ------------------------------------------------------------------------
+// Propósito: Verifica que una lista tenga entre 1 y MAX_STAT_VALUES valores
+// ------------------------------------------------------------------------
+fn check_stat_values(values: &Vec<i128>) -> Result<(), CalculatorError> {
+    if values.is_empty() {
+        return Err(CalculatorError::EmptyInput);
+    }
+    if values.len() as usize > MAX_STAT_VALUES {
+        return Err(CalculatorError::TooManyValues);
+    }
+    Ok(())
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: checked_sum
+// ------------------------------------------------------------------------
+// Propósito: Suma los valores de una lista válida, u Overflow
+// ------------------------------------------------------------------------
+fn checked_sum(values: &Vec<i128>) -> Result<i128, CalculatorError> {
+    check_stat_values(values)?;
+    values
+        .iter()
+        .try_fold(0i128, |total, value| total.checked_add(value))
+        .ok_or(CalculatorError::Overflow)
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: midpoint
+// ------------------------------------------------------------------------
+// Propósito: (a + b) / 2 truncado hacia cero, sin calcular a + b (que puede
+//            desbordarse con valores cercanos a i128::MAX)
+// ------------------------------------------------------------------------
+fn midpoint(a: i128, b: i128) -> i128 {
+    // Bits comunes más la mitad de los bits distintos = (a + b) / 2 hacia abajo
+    let floor = (a & b) + ((a ^ b) >> 1);
+    // Si la suma es impar y negativa, truncar hacia cero es uno más que floor
+    if floor < 0 && (a ^ b) & 1 == 1 {
+        floor + 1
+    } else {
+        floor
+    }
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: population_variance
+// ------------------------------------------------------------------------
+// Propósito: Varianza poblacional exacta, truncada, sin decimales
+// Con m el promedio truncado y r = suma - n × m (el resto, |r| < n):
+//   Σ(x - μ)² = Σ(x - m)² - r² / n
+// y entonces la varianza es (n × Σ(x - m)² - r²) / n²
+// ------------------------------------------------------------------------
+fn population_variance(values: &Vec<i128>) -> Result<i128, CalculatorError> {
+    let total = checked_sum(values)?;
+    let n = values.len() as i128;
+    let mean = total / n;
+    let remainder = total % n;
+
+    let mut squares: i128 = 0;
+    for value in values.iter() {
+        let deviation = value.checked_sub(mean).ok_or(CalculatorError::Overflow)?;
+        let square = deviation.checked_mul(deviation).ok_or(CalculatorError::Overflow)?;
+        squares = squares.checked_add(square).ok_or(CalculatorError::Overflow)?;
+    }
+
+    let numerator = squares
+        .checked_mul(n)
+        .ok_or(CalculatorError::Overflow)?
+        - remainder * remainder;
+    Ok(numerator / (n * n))
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: isqrt_u128
+// ------------------------------------------------------------------------
+// Propósito: Raíz cuadrada entera (hacia abajo) con el método de Newton
+// Empezamos en una potencia de 2 mayor o igual a la raíz; desde ahí cada
+// paso baja y se detiene justo en la raíz entera
+// ------------------------------------------------------------------------
+fn isqrt_u128(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let bits = 128 - n.leading_zeros();
+    let mut x = 1u128 << bits.div_ceil(2);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: nth_root_u128
+// ------------------------------------------------------------------------
+// Propósito: Raíz k-ésima entera, construida bit a bit desde el más alto:
+//            cada bit queda encendido solo si (raíz con ese bit)^k <= n
+// La raíz de un número de b bits tiene como mucho ceil(b / k) bits, así que
+// basta con probar esos bits; checked_pow descarta los candidatos que se
+// desbordan (que obviamente superan a n)
+// ------------------------------------------------------------------------
+fn nth_root_u128(n: u128, k: u32) -> Result<u128, CalculatorError> {
+    if k == 0 {
+        return Err(CalculatorError::OutOfDomain);
+    }
+    if k == 1 || n < 2 {
+        return Ok(n);
+    }
+
+    let bits = 128 - n.leading_zeros();
+    let mut root: u128 = 0;
+    for bit in (0..bits.div_ceil(k)).rev() {
+        let candidate = root | (1 << bit);
+        if candidate.checked_pow(k).is_some_and(|power| power <= n) {
+            root = candidate;
+        }
+    }
+    Ok(root)
+}
+
+// ------------------------------------------------------------------------
+// FUNCIONES AUXILIARES: límites de 256 bits
+// ------------------------------------------------------------------------
+fn u256_max(env: &Env) -> U256 {
+    U256::from_parts(env, u64::MAX, u64::MAX, u64::MAX, u64::MAX)
+}
+
+fn i256_max(env: &Env) -> I256 {
+    I256::from_parts(env, i64::MAX, u64::MAX, u64::MAX, u64::MAX)
+}
+
+fn i256_min(env: &Env) -> I256 {
+    I256::from_parts(env, i64::MIN, 0, 0, 0)
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: u256_checked_mul
+// ------------------------------------------------------------------------
+// Propósito: a × b, verificando antes que no se desborde (b > MAX / a)
+// ------------------------------------------------------------------------
+fn u256_checked_mul(env: &Env, a: &U256, b: &U256) -> Result<U256, CalculatorError> {
+    let zero = U256::from_u32(env, 0);
+    if *a != zero && *b > u256_max(env).div(a) {
+        return Err(CalculatorError::Overflow);
+    }
+    Ok(a.mul(b))
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: i256_checked_mul
+// ------------------------------------------------------------------------
+// Propósito: a × b, verificando antes que no se desborde
+// Comparamos contra MAX / x o MIN / x según los signos; la división trunca
+// hacia cero, lo que da el límite exacto en cada caso
+// ------------------------------------------------------------------------
+fn i256_checked_mul(env: &Env, a: &I256, b: &I256) -> Result<I256, CalculatorError> {
+    let zero = I256::from_i32(env, 0);
+    let (max, min) = (i256_max(env), i256_min(env));
+
+    let overflow = if *a > zero {
+        if *b > zero {
+            *a > max.div(b)
+        } else {
+            *b < min.div(a)
+        }
+    } else if *b > zero {
+        *a < min.div(b)
+    } else {
+        // Ambos <= 0: el producto es >= 0 y solo se desborda por arriba
+        *a != zero && *b < max.div(a)
+    };
+
+    if overflow {
+        return Err(CalculatorError::Overflow);
+    }
+    Ok(a.mul(b))
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: ordering_to_i32
+// ------------------------------------------------------------------------
+// Propósito: Convierte un Ordering de Rust en -1, 0 o 1
+// ------------------------------------------------------------------------
+fn ordering_to_i32(ordering: Ordering) -> i32 {
+    match ordering {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: scale_factor
+// ------------------------------------------------------------------------
+// Propósito: Calcula 10^scale, el valor que representa 1 en punto fijo
+// Retorna: 10^scale, o InvalidScale si scale > MAX_SCALE
+// ------------------------------------------------------------------------
+fn scale_factor(scale: u32) -> Result<i128, CalculatorError> {
+    if scale > MAX_SCALE {
+        return Err(CalculatorError::InvalidScale);
+    }
+    Ok(10i128.pow(scale))
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: mul_div
+// ------------------------------------------------------------------------
+// Propósito: Calcula a × b / denominator con redondeo, sin desbordar en el
+//            producto intermedio (que se guarda en 256 bits)
+// Retorna: El resultado redondeado, DivisionByZero u Overflow si el
+//          resultado final no cabe en i128
+// ------------------------------------------------------------------------
+fn mul_div(
+    a: i128,
+    b: i128,
+    denominator: i128,
+    mode: RoundingMode,
+) -> Result<i128, CalculatorError> {
+    if denominator == 0 {
+        return Err(CalculatorError::DivisionByZero);
+    }
+
+    // Trabajamos con los valores absolutos y aplicamos el signo al final
+    let negative = ((a < 0) ^ (b < 0) ^ (denominator < 0)) && a != 0 && b != 0;
+    let divisor = denominator.unsigned_abs();
+    let (high, low) = widening_mul(a.unsigned_abs(), b.unsigned_abs());
+    let (quotient, remainder) = divide_wide(high, low, divisor)?;
+
+    // Redondeamos según el modo, igual que divide_rounded()
+    let away_from_zero = remainder != 0
+        && match mode {
+            RoundingMode::Trunc => false,
+            RoundingMode::Floor => negative,
+            RoundingMode::Ceil => !negative,
+            RoundingMode::HalfEven => {
+                // remainder >= divisor - remainder equivale a 2 × resto >= divisor
+                let half = divisor - remainder;
+                remainder > half || (remainder == half && quotient % 2 != 0)
+            }
+        };
+    let magnitude = if away_from_zero {
+        quotient.checked_add(1).ok_or(CalculatorError::Overflow)?
+    } else {
+        quotient
+    };
+
+    // Volvemos a i128 con el signo (el negativo admite un valor más: 2^127)
+    if negative {
+        0i128
+            .checked_sub_unsigned(magnitude)
+            .ok_or(CalculatorError::Overflow)
+    } else {
+        i128::try_from(magnitude).map_err(|_| CalculatorError::Overflow)
+    }
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: widening_mul
+// ------------------------------------------------------------------------
+// Propósito: Multiplica dos u128 y retorna el producto de 256 bits como
+//            (128 bits altos, 128 bits bajos), usando mitades de 64 bits
+// ------------------------------------------------------------------------
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & MASK);
+    let (b_high, b_low) = (b >> 64, b & MASK);
+
+    // Productos parciales de 64 × 64 bits (cada uno cabe en u128)
+    let low_low = a_low * b_low;
+    let low_high = a_low * b_high;
+    let high_low = a_high * b_low;
+    let high_high = a_high * b_high;
+
+    // Sumamos la columna del medio guardando el acarreo
+    let middle = (low_low >> 64) + (low_high & MASK) + (high_low & MASK);
+    let low = (middle << 64) | (low_low & MASK);
+    let high = high_high + (low_high >> 64) + (high_low >> 64) + (middle >> 64);
+
+    (high, low)
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: divide_wide
+// ------------------------------------------------------------------------
+// Propósito: Divide un número de 256 bits (high, low) por un u128, bit a bit
+// Retorna: (cociente, resto), u Overflow si el cociente no cabe en u128
+// Nota: divisor <= 2^127, así que el resto desplazado nunca se desborda
+// ------------------------------------------------------------------------
+fn divide_wide(high: u128, low: u128, divisor: u128) -> Result<(u128, u128), CalculatorError> {
+    // Si la parte alta ya es >= divisor, el cociente necesita más de 128 bits
+    if high >= divisor {
+        return Err(CalculatorError::Overflow);
+    }
+
+    let mut remainder = high;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if remainder >= divisor {
+            remainder -= divisor;
+            quotient |= 1;
+        }
+    }
+
+    Ok((quotient, remainder))
+}
+
+// ------------------------------------------------------------------------
+// FUNCIÓN AUXILIAR: divide_rounded
+// ------------------------------------------------------------------------
+// Propósito: División entera con redondeo explícito (no es parte del contrato)
+// Parámetros:
+//   - a: Dividendo
+//   - b: Divisor
+//   - mode: Modo de redondeo
+// Retorna: El cociente redondeado, DivisionByZero u Overflow (i128::MIN / -1)
+// ------------------------------------------------------------------------
+fn divide_rounded(a: i128, b: i128, mode: RoundingMode) -> Result<i128, CalculatorError> {
+    if b == 0 {
+        return Err(CalculatorError::DivisionByZero);
+    }
+
+    // Rust divide truncando hacia cero; si hay resto, ajustamos según el modo
+    let quotient = a.checked_div(b).ok_or(CalculatorError::Overflow)?;
+    let remainder = a % b;
+    if remainder == 0 {
+        return Ok(quotient);
+    }
+
+    // ¿El resultado exacto es negativo? Entonces truncar fue redondear hacia arriba
+    let negative = (remainder < 0) != (b < 0);
+    let away_from_zero = match mode {
+        RoundingMode::Trunc => false,
+        RoundingMode::Floor => negative,
+        RoundingMode::Ceil => !negative,
+        RoundingMode::HalfEven => {
+            // Comparamos el doble del resto con el divisor (en u128 no se desborda)
+            let twice_remainder = remainder.unsigned_abs() * 2;
+            let divisor = b.unsigned_abs();
+            twice_remainder > divisor || (twice_remainder == divisor && quotient % 2 != 0)
+        }
+    };
+
+    if !away_from_zero {
+        Ok(quotient)
+    } else if negative {
+        quotient.checked_sub(1).ok_or(CalculatorError::Overflow)
+    } else {
+        quotient.checked_add(1).ok_or(CalculatorError::Overflow)
     }
 }
 
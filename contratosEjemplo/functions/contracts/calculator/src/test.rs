@@ -1,9 +1,15 @@
 // Esta línea indica que este módulo solo se compila cuando ejecutamos tests
 #![cfg(test)]
+extern crate std;
 
 // Importamos todos los elementos del módulo padre y tipos de Soroban SDK
 use super::*;
-use soroban_sdk::{vec, Env, String};
+use soroban_sdk::{
+    testutils::{
+        storage::Persistent, Address as _, AuthorizedFunction, AuthorizedInvocation, Ledger,
+    },
+    Address, Env, IntoVal, String, Symbol, symbol_short, vec, Vec, I256, U256,
+};
 
 // Prueba para la función de saludo (greet)
 #[test]
@@ -58,9 +64,11 @@ fn test_calculate_and_store() {
     let env = Env::default();
     let contract_id = env.register(CalculatorContract, ());
     let client = CalculatorContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let user = Address::generate(&env);
 
     // Probamos la función que calcula y guarda el resultado
-    let result = client.calculate_and_store(&10, &20);
+    let result = client.calculate_and_store(&user, &10, &Operation::Add, &20);
     
     // Verificamos que la suma sea correcta (10 + 20 = 30)
     assert_eq!(result, 30);
@@ -72,13 +80,755 @@ fn test_get_last_calculation() {
     let env = Env::default();
     let contract_id = env.register(CalculatorContract, ());
     let client = CalculatorContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let user = Address::generate(&env);
+
+    // Sin cálculos guardados no hay nada que recuperar
+    assert_eq!(client.get_last_calculation(&user), None);
 
     // Primero guardamos un cálculo (15 + 25 = 40)
-    client.calculate_and_store(&15, &25);
+    client.calculate_and_store(&user, &15, &Operation::Add, &25);
     
     // Luego recuperamos el último cálculo almacenado
-    let result = client.get_last_calculation();
+    let result = client.get_last_calculation(&user).unwrap();
     
-    // Verificamos que recuperemos correctamente el valor 40
-    assert_eq!(result, 40);
+    // Verificamos que recuperemos el cálculo completo: 15 + 25 = 40
+    assert_eq!(
+        result,
+        Calculation { a: 15, operation: Operation::Add, b: 25, result: 40 }
+    );
+}
+
+// Prueba para los errores de desbordamiento
+#[test]
+fn test_overflow_errors() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let user = Address::generate(&env);
+
+    // Los try_ retornan el error en lugar de abortar la transacción
+    assert_eq!(client.try_add(&i32::MAX, &1), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_add(&i32::MIN, &-1), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_multiply(&i32::MAX, &2), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_multiply(&i32::MIN, &-1), Err(Ok(CalculatorError::Overflow)));
+
+    // Los valores límite que sí caben se calculan normalmente
+    assert_eq!(client.add(&i32::MAX, &i32::MIN), -1);
+    assert_eq!(client.multiply(&i32::MIN, &1), i32::MIN);
+
+    // Un cálculo que se desborda no reemplaza el último guardado
+    client.calculate_and_store(&user, &1, &Operation::Add, &2);
+    let result = client.try_calculate_and_store(&user, &i32::MAX, &Operation::Add, &1);
+    assert_eq!(result, Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.get_last_calculation(&user).unwrap().result, 3);
+}
+
+// Prueba para la resta
+#[test]
+fn test_subtract() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+
+    assert_eq!(client.subtract(&5, &3), 2);
+    assert_eq!(client.subtract(&3, &5), -2);
+    assert_eq!(client.try_subtract(&i32::MIN, &1), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_subtract(&0, &i32::MIN), Err(Ok(CalculatorError::Overflow)));
+}
+
+// Prueba para la división con cada modo de redondeo
+#[test]
+fn test_divide_rounding() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+
+    // (dividendo, divisor, Trunc, Floor, Ceil, HalfEven)
+    let cases = [
+        (7, 2, 3, 3, 4, 4),     // 3.5: el empate va al par (4)
+        (5, 2, 2, 2, 3, 2),     // 2.5: el empate va al par (2)
+        (-7, 2, -3, -4, -3, -4),
+        (7, -2, -3, -4, -3, -4),
+        (-7, -2, 3, 3, 4, 4),
+        (10, 3, 3, 3, 4, 3),    // 3.33...
+        (11, 3, 3, 3, 4, 4),    // 3.66...
+        (-11, 3, -3, -4, -3, -4),
+        (9, 3, 3, 3, 3, 3),     // Exacta: todos los modos coinciden
+    ];
+    for (a, b, trunc, floor, ceil, half_even) in cases {
+        assert_eq!(client.divide(&a, &b, &RoundingMode::Trunc), trunc);
+        assert_eq!(client.divide(&a, &b, &RoundingMode::Floor), floor);
+        assert_eq!(client.divide(&a, &b, &RoundingMode::Ceil), ceil);
+        assert_eq!(client.divide(&a, &b, &RoundingMode::HalfEven), half_even);
+    }
+}
+
+// Prueba para los casos límite de la división y el resto
+#[test]
+fn test_division_edge_cases() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+
+    // i32::MIN / -1 = 2^31, que no cabe en i32
+    for mode in [RoundingMode::Trunc, RoundingMode::Floor, RoundingMode::Ceil, RoundingMode::HalfEven] {
+        assert_eq!(client.try_divide(&i32::MIN, &-1, &mode), Err(Ok(CalculatorError::Overflow)));
+        assert_eq!(client.try_divide(&1, &0, &mode), Err(Ok(CalculatorError::DivisionByZero)));
+    }
+    assert_eq!(client.divide(&i32::MIN, &1, &RoundingMode::Trunc), i32::MIN);
+    assert_eq!(client.divide(&i32::MIN, &2, &RoundingMode::Floor), i32::MIN / 2);
+    assert_eq!(client.divide(&i32::MAX, &-1, &RoundingMode::Trunc), -i32::MAX);
+
+    // El resto usa el signo del dividendo, y i32::MIN % -1 es 0
+    assert_eq!(client.modulo(&7, &3), 1);
+    assert_eq!(client.modulo(&-7, &3), -1);
+    assert_eq!(client.modulo(&7, &-3), 1);
+    assert_eq!(client.modulo(&i32::MIN, &-1), 0);
+    assert_eq!(client.try_modulo(&7, &0), Err(Ok(CalculatorError::DivisionByZero)));
+}
+
+// Prueba para la potencia, el valor absoluto y el cambio de signo
+#[test]
+fn test_pow_abs_negate() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+
+    assert_eq!(client.pow(&2, &10), 1024);
+    assert_eq!(client.pow(&-3, &3), -27);
+    assert_eq!(client.pow(&0, &0), 1);
+    assert_eq!(client.pow(&-2, &31), i32::MIN);
+    assert_eq!(client.try_pow(&2, &31), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_pow(&10, &10), Err(Ok(CalculatorError::Overflow)));
+
+    assert_eq!(client.abs(&-5), 5);
+    assert_eq!(client.abs(&i32::MAX), i32::MAX);
+    assert_eq!(client.try_abs(&i32::MIN), Err(Ok(CalculatorError::Overflow)));
+
+    assert_eq!(client.negate(&5), -5);
+    assert_eq!(client.negate(&i32::MAX), -i32::MAX);
+    assert_eq!(client.try_negate(&i32::MIN), Err(Ok(CalculatorError::Overflow)));
+}
+
+// Prueba para calculate_and_store con cada operación
+#[test]
+fn test_calculate_and_store_operations() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let user = Address::generate(&env);
+
+    let cases = [
+        (5, Operation::Add, 3, 8),
+        (5, Operation::Sub, 3, 2),
+        (5, Operation::Mul, 3, 15),
+        (-7, Operation::Div, 2, -3),
+        (-7, Operation::Mod, 3, -1),
+        (2, Operation::Pow, 10, 1024),
+    ];
+    for (a, operation, b, result) in cases {
+        assert_eq!(client.calculate_and_store(&user, &a, &operation, &b), result);
+        assert_eq!(
+            client.get_last_calculation(&user),
+            Some(Calculation { a, operation, b, result })
+        );
+    }
+
+    // Los errores se propagan y no se guarda nada
+    let errors = [
+        (1, Operation::Div, 0, CalculatorError::DivisionByZero),
+        (1, Operation::Mod, 0, CalculatorError::DivisionByZero),
+        (i32::MIN, Operation::Div, -1, CalculatorError::Overflow),
+        (2, Operation::Pow, -1, CalculatorError::NegativeExponent),
+        (2, Operation::Pow, 31, CalculatorError::Overflow),
+    ];
+    for (a, operation, b, error) in errors {
+        assert_eq!(client.try_calculate_and_store(&user, &a, &operation, &b), Err(Ok(error)));
+    }
+    assert_eq!(client.get_last_calculation(&user).unwrap().result, 1024);
+}
+
+// Prueba para el historial por usuario (buffer circular en persistent storage)
+#[test]
+fn test_history_per_user() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    // Cada cálculo requiere la firma del usuario
+    client.calculate_and_store(&alice, &1, &Operation::Add, &1);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            alice.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    contract_id.clone(),
+                    Symbol::new(&env, "calculate_and_store"),
+                    (alice.clone(), 1i32, Operation::Add, 1i32).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+
+    // Los usuarios no pisan los resultados de otros
+    client.calculate_and_store(&bob, &2, &Operation::Mul, &3);
+    assert_eq!(client.get_last_calculation(&alice).unwrap().result, 2);
+    assert_eq!(client.get_last_calculation(&bob).unwrap().result, 6);
+
+    // Con más de HISTORY_SIZE cálculos se conservan solo los más recientes
+    for i in 2..=(HISTORY_SIZE as i32 + 3) {
+        client.calculate_and_store(&alice, &i, &Operation::Add, &0);
+    }
+    let history = client.get_history(&alice, &100);
+    assert_eq!(history.len(), HISTORY_SIZE);
+    for (position, calculation) in history.iter().enumerate() {
+        assert_eq!(calculation.result, HISTORY_SIZE as i32 + 3 - position as i32);
+    }
+    let recent = client.get_history(&alice, &3);
+    assert_eq!(recent.len(), 3);
+    assert_eq!(recent.get(0).unwrap().result, HISTORY_SIZE as i32 + 3);
+    assert_eq!(recent.get(2).unwrap().result, HISTORY_SIZE as i32 + 1);
+
+    // El historial vive en persistent storage con su TTL extendido
+    let ttl = env.as_contract(&contract_id, || {
+        env.storage().persistent().get_ttl(&DataKey::History(alice.clone()))
+    });
+    assert_eq!(ttl, TTL_EXTEND);
+
+    // Consultar el historial también extiende su TTL
+    // (la instancia se mantiene viva para poder avanzar el ledger)
+    let history_ttl = || {
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().get_ttl(&DataKey::History(alice.clone()))
+        })
+    };
+    env.as_contract(&contract_id, || env.storage().instance().extend_ttl(TTL_EXTEND, 2 * TTL_EXTEND));
+    env.ledger().with_mut(|li| li.sequence_number += TTL_EXTEND - TTL_THRESHOLD + 1);
+    assert!(history_ttl() < TTL_THRESHOLD);
+    client.get_last_calculation(&alice);
+    assert_eq!(history_ttl(), TTL_EXTEND);
+
+    // Borrar el historial de alice no afecta el de bob
+    client.clear_history(&alice);
+    assert_eq!(client.get_history(&alice, &10).len(), 0);
+    assert_eq!(client.get_last_calculation(&alice), None);
+    assert_eq!(client.get_history(&bob, &10).len(), 1);
+}
+
+// Prueba para los registros de memoria (M+, M-, MR, MC)
+#[test]
+fn test_memory_registers() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let m1 = symbol_short!("m1");
+    let m2 = symbol_short!("m2");
+
+    // Un registro vacío vale 0
+    assert_eq!(client.memory_recall(&alice, &m1), 0);
+
+    // M+ y M- acumulan sobre el valor actual
+    assert_eq!(client.memory_add(&alice, &m1, &10), 10);
+    assert_eq!(client.memory_add(&alice, &m1, &5), 15);
+    assert_eq!(client.memory_sub(&alice, &m1, &20), -5);
+    assert_eq!(client.memory_recall(&alice, &m1), -5);
+
+    // Los registros son independientes por nombre y por usuario
+    client.memory_add(&alice, &m2, &7);
+    assert_eq!(client.memory_recall(&alice, &m2), 7);
+    assert_eq!(client.memory_recall(&bob, &m1), 0);
+
+    // Los registros se pueden usar como operando
+    assert_eq!(client.add_from_memory(&alice, &m2, &3), 10);
+    assert_eq!(client.calculate_from_memory(&alice, &m2, &Operation::Mul, &3), 21);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            alice.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    contract_id.clone(),
+                    Symbol::new(&env, "calculate_from_memory"),
+                    (alice.clone(), m2.clone(), Operation::Mul, 3i32).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert_eq!(
+        client.try_calculate_from_memory(&alice, &m2, &Operation::Div, &0),
+        Err(Ok(CalculatorError::DivisionByZero))
+    );
+
+    // Los desbordamientos no modifican el registro
+    client.memory_add(&bob, &m1, &i32::MAX);
+    assert_eq!(client.try_memory_add(&bob, &m1, &1), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_add_from_memory(&bob, &m1, &1), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.memory_recall(&bob, &m1), i32::MAX);
+
+    // Los registros viven en persistent storage con su TTL extendido
+    let ttl = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&DataKey::Register(alice.clone(), m1.clone()))
+    });
+    assert_eq!(ttl, TTL_EXTEND);
+
+    // Leer un registro (MR) también extiende su TTL
+    // (la instancia se mantiene viva para poder avanzar el ledger)
+    let register_ttl = || {
+        env.as_contract(&contract_id, || {
+            env.storage()
+                .persistent()
+                .get_ttl(&DataKey::Register(alice.clone(), m1.clone()))
+        })
+    };
+    env.as_contract(&contract_id, || env.storage().instance().extend_ttl(TTL_EXTEND, 2 * TTL_EXTEND));
+    env.ledger().with_mut(|li| li.sequence_number += TTL_EXTEND - TTL_THRESHOLD + 1);
+    assert!(register_ttl() < TTL_THRESHOLD);
+    client.memory_recall(&alice, &m1);
+    assert_eq!(register_ttl(), TTL_EXTEND);
+
+    // MC borra solo ese registro
+    client.memory_clear(&alice, &m1);
+    assert_eq!(client.memory_recall(&alice, &m1), 0);
+    assert_eq!(client.memory_recall(&alice, &m2), 7);
+}
+
+// Prueba para la aritmética de punto fijo
+#[test]
+fn test_fixed_point() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+    let one = 10_000_000i128; // 1.0 con STROOPS_SCALE (7 decimales)
+
+    // Suma y resta conservan la escala
+    assert_eq!(client.fixed_add(&15_000_000, &22_500_000), 37_500_000);
+    assert_eq!(client.fixed_sub(&15_000_000, &22_500_000), -7_500_000);
+    assert_eq!(client.try_fixed_add(&i128::MAX, &1), Err(Ok(CalculatorError::Overflow)));
+
+    // 1.5 × 2.25 = 3.375 (exacto: todos los modos coinciden)
+    for mode in [RoundingMode::Floor, RoundingMode::Ceil, RoundingMode::HalfEven] {
+        assert_eq!(client.fixed_mul(&15_000_000, &22_500_000, &STROOPS_SCALE, &mode), 33_750_000);
+    }
+
+    // 1 / 3 = 0.3333333... y 2 / 3 = 0.6666666...
+    let third = |mode| client.fixed_div(&one, &(3 * one), &STROOPS_SCALE, &mode);
+    assert_eq!(third(RoundingMode::Floor), 3_333_333);
+    assert_eq!(third(RoundingMode::Ceil), 3_333_334);
+    assert_eq!(third(RoundingMode::HalfEven), 3_333_333);
+    let two_thirds = client.fixed_div(&(2 * one), &(3 * one), &STROOPS_SCALE, &RoundingMode::HalfEven);
+    assert_eq!(two_thirds, 6_666_667);
+
+    // Con negativos, Floor va hacia menos infinito y Ceil hacia cero
+    let minus_third = |mode| client.fixed_div(&-one, &(3 * one), &STROOPS_SCALE, &mode);
+    assert_eq!(minus_third(RoundingMode::Floor), -3_333_334);
+    assert_eq!(minus_third(RoundingMode::Ceil), -3_333_333);
+
+    // Empates con escala 1: 0.5 × 0.5 = 0.25 y 1.5 × 0.5 = 0.75
+    assert_eq!(client.fixed_mul(&5, &5, &1, &RoundingMode::HalfEven), 2);
+    assert_eq!(client.fixed_mul(&15, &5, &1, &RoundingMode::HalfEven), 8);
+    assert_eq!(client.fixed_mul(&-15, &5, &1, &RoundingMode::HalfEven), -8);
+    assert_eq!(client.fixed_mul(&5, &5, &1, &RoundingMode::Ceil), 3);
+
+    // El producto intermedio no se desborda: i128::MAX × 1.0 = i128::MAX
+    assert_eq!(client.fixed_mul(&i128::MAX, &one, &STROOPS_SCALE, &RoundingMode::Floor), i128::MAX);
+    assert_eq!(client.fixed_mul(&i128::MIN, &one, &STROOPS_SCALE, &RoundingMode::Floor), i128::MIN);
+    assert_eq!(client.fixed_div(&i128::MAX, &one, &STROOPS_SCALE, &RoundingMode::Floor), i128::MAX);
+
+    // Errores: resultado que no cabe, división por cero y escala inválida
+    let result = client.try_fixed_mul(&i128::MAX, &(2 * one), &STROOPS_SCALE, &RoundingMode::Floor);
+    assert_eq!(result, Err(Ok(CalculatorError::Overflow)));
+    let result = client.try_fixed_div(&i128::MAX, &(one / 2), &STROOPS_SCALE, &RoundingMode::Floor);
+    assert_eq!(result, Err(Ok(CalculatorError::Overflow)));
+    let result = client.try_fixed_div(&one, &0, &STROOPS_SCALE, &RoundingMode::Floor);
+    assert_eq!(result, Err(Ok(CalculatorError::DivisionByZero)));
+    let result = client.try_fixed_mul(&one, &one, &39, &RoundingMode::Floor);
+    assert_eq!(result, Err(Ok(CalculatorError::InvalidScale)));
+}
+
+// Prueba para las operaciones con U256
+#[test]
+fn test_u256_operations() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+    let n = |value: u128| U256::from_u128(&env, value);
+    let max = U256::from_parts(&env, u64::MAX, u64::MAX, u64::MAX, u64::MAX);
+    let two_pow_128 = U256::from_parts(&env, 0, 1, 0, 0);
+    let two_pow_255 = U256::from_parts(&env, 1 << 63, 0, 0, 0);
+
+    // Valores que ya no caben en u128/i128
+    assert_eq!(client.u256_add(&n(u128::MAX), &n(1)), two_pow_128);
+    assert_eq!(client.u256_mul(&two_pow_128, &n(1 << 127)), two_pow_255);
+    assert_eq!(client.u256_sub(&two_pow_128, &n(1)), n(u128::MAX));
+    assert_eq!(client.u256_div(&two_pow_255, &n(1 << 127)), two_pow_128);
+    assert_eq!(client.u256_pow(&n(2), &255), two_pow_255);
+
+    // 10^77 es la mayor potencia de 10 que cabe en U256 (MAX ≈ 1.16 × 10^77)
+    let ten_pow_77 = client.u256_pow(&n(10), &77);
+    assert_eq!(client.u256_div(&ten_pow_77, &client.u256_pow(&n(10), &40)).to_u128(), Some(10u128.pow(37)));
+    assert_eq!(client.try_u256_pow(&n(10), &78), Err(Ok(CalculatorError::Overflow)));
+
+    // Desbordamientos y división por cero
+    assert_eq!(client.u256_add(&max.sub(&n(1)), &n(1)), max);
+    assert_eq!(client.try_u256_add(&max, &n(1)), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_u256_sub(&n(0), &n(1)), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_u256_mul(&two_pow_128, &two_pow_128), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_u256_pow(&n(2), &256), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_u256_div(&max, &n(0)), Err(Ok(CalculatorError::DivisionByZero)));
+    assert_eq!(client.u256_pow(&max, &1), max);
+    assert_eq!(client.u256_pow(&max, &0), n(1));
+
+    // Comparaciones
+    assert_eq!(client.u256_cmp(&two_pow_128, &n(u128::MAX)), 1);
+    assert_eq!(client.u256_cmp(&n(5), &n(5)), 0);
+    assert_eq!(client.u256_cmp(&n(0), &max), -1);
+}
+
+// Prueba para las operaciones con I256
+#[test]
+fn test_i256_operations() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+    let n = |value: i128| I256::from_i128(&env, value);
+    let max = I256::from_parts(&env, i64::MAX, u64::MAX, u64::MAX, u64::MAX);
+    let min = I256::from_parts(&env, i64::MIN, 0, 0, 0);
+    let two_pow_128 = I256::from_parts(&env, 0, 1, 0, 0);
+
+    // Valores que ya no caben en i128
+    assert_eq!(client.i256_add(&n(i128::MAX), &n(1)), I256::from_parts(&env, 0, 0, 1 << 63, 0));
+    assert_eq!(client.i256_sub(&n(i128::MIN), &n(i128::MAX)), I256::from_parts(&env, -1, u64::MAX, 0, 1));
+    assert_eq!(client.i256_mul(&two_pow_128, &n(i128::MIN)), min);
+    assert_eq!(client.i256_div(&min, &two_pow_128), n(i128::MIN));
+    assert_eq!(client.i256_pow(&n(-2), &255), min);
+    assert_eq!(client.i256_pow(&n(-3), &3), n(-27));
+
+    // Desbordamientos en ambos extremos
+    assert_eq!(client.try_i256_add(&max, &n(1)), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_i256_add(&min, &n(-1)), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_i256_sub(&min, &n(1)), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_i256_sub(&n(0), &min), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_i256_mul(&min, &n(-1)), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_i256_mul(&max, &n(2)), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_i256_mul(&min, &n(2)), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_i256_mul(&n(-2), &max), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_i256_pow(&n(2), &255), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_i256_div(&min, &n(-1)), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_i256_div(&n(1), &n(0)), Err(Ok(CalculatorError::DivisionByZero)));
+
+    // Los límites exactos sí se calculan
+    assert_eq!(client.i256_add(&max, &min), n(-1));
+    assert_eq!(client.i256_mul(&max, &n(-1)), min.add(&n(1)));
+    assert_eq!(client.i256_mul(&min, &n(1)), min);
+    assert_eq!(client.i256_div(&n(-7), &n(2)), n(-3));
+
+    // Comparaciones
+    assert_eq!(client.i256_cmp(&min, &max), -1);
+    assert_eq!(client.i256_cmp(&n(-1), &n(-1)), 0);
+    assert_eq!(client.i256_cmp(&two_pow_128, &n(i128::MAX)), 1);
+}
+
+// Prueba para evaluate() con expresiones en notación polaca inversa
+#[test]
+fn test_evaluate_rpn() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+    let num = Token::Num;
+    let op = Token::Op;
+
+    // (3 + 4) × 2 = 14
+    let expr = vec![&env, num(3), num(4), op(Operation::Add), num(2), op(Operation::Mul)];
+    assert_eq!(client.evaluate(&expr), 14);
+
+    // El orden de los operandos importa: 10 - 4 = 6 y 2 ^ 10 = 1024
+    assert_eq!(client.evaluate(&vec![&env, num(10), num(4), op(Operation::Sub)]), 6);
+    assert_eq!(client.evaluate(&vec![&env, num(2), num(10), op(Operation::Pow)]), 1024);
+    assert_eq!(client.evaluate(&vec![&env, num(42)]), 42);
+
+    // Trabaja en i128: 2^100 no cabe en i32
+    let expr = vec![&env, num(2), num(100), op(Operation::Pow), num(1 << 100), op(Operation::Div)];
+    assert_eq!(client.evaluate(&expr), 1);
+
+    // Expresiones mal formadas
+    let malformed = Err(Ok(CalculatorError::MalformedExpression));
+    assert_eq!(client.try_evaluate(&vec![&env]), malformed);
+    assert_eq!(client.try_evaluate(&vec![&env, op(Operation::Add)]), malformed);
+    assert_eq!(client.try_evaluate(&vec![&env, num(1), op(Operation::Add)]), malformed);
+    assert_eq!(client.try_evaluate(&vec![&env, num(1), num(2)]), malformed);
+
+    // Los errores de cada operación se propagan
+    let expr = vec![&env, num(1), num(0), op(Operation::Div)];
+    assert_eq!(client.try_evaluate(&expr), Err(Ok(CalculatorError::DivisionByZero)));
+    let expr = vec![&env, num(i128::MAX), num(1), op(Operation::Add)];
+    assert_eq!(client.try_evaluate(&expr), Err(Ok(CalculatorError::Overflow)));
+    let expr = vec![&env, num(2), num(-1), op(Operation::Pow)];
+    assert_eq!(client.try_evaluate(&expr), Err(Ok(CalculatorError::NegativeExponent)));
+
+    // 32 números apilados caben; el 33 supera MAX_STACK_DEPTH
+    let mut expr = vec![&env];
+    for _ in 0..32 {
+        expr.push_back(num(1));
+    }
+    for _ in 0..31 {
+        expr.push_back(op(Operation::Add));
+    }
+    assert_eq!(client.evaluate(&expr), 32);
+    expr.push_front(num(1));
+    assert_eq!(client.try_evaluate(&expr), Err(Ok(CalculatorError::StackTooDeep)));
+
+    // Más de 128 tokens se rechaza aunque la pila no crezca
+    let mut expr = vec![&env, num(0)];
+    for _ in 0..64 {
+        expr.push_back(num(1));
+        expr.push_back(op(Operation::Add));
+    }
+    assert_eq!(client.try_evaluate(&expr), Err(Ok(CalculatorError::ExpressionTooLong)));
+}
+
+// Prueba para el parser de expresiones infijas (shunting-yard)
+#[test]
+fn test_evaluate_infix() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+    let eval = |expr: &str| client.try_evaluate_infix(&String::from_str(&env, expr));
+
+    // La conversión a RPN respeta paréntesis y precedencia
+    let rpn = client.to_rpn(&String::from_str(&env, "(3 + 4) * 2"));
+    let expected = vec![
+        &env,
+        Token::Num(3),
+        Token::Num(4),
+        Token::Op(Operation::Add),
+        Token::Num(2),
+        Token::Op(Operation::Mul),
+    ];
+    assert_eq!(rpn, expected);
+
+    // Precedencia y asociatividad
+    assert_eq!(eval("(3 + 4) * 2"), Ok(Ok(14)));
+    assert_eq!(eval("3 + 4 * 2"), Ok(Ok(11)));
+    assert_eq!(eval("10 - 4 - 3"), Ok(Ok(3)));
+    assert_eq!(eval("100 / 10 / 5"), Ok(Ok(2)));
+    assert_eq!(eval("2 ^ 3 ^ 2"), Ok(Ok(512)));
+    assert_eq!(eval("2 * 3 ^ 2"), Ok(Ok(18)));
+    assert_eq!(eval("17 % 5 * 2"), Ok(Ok(4)));
+    assert_eq!(eval("((((7))))"), Ok(Ok(7)));
+
+    // Números negativos: el '-' pegado a un número es parte del literal
+    assert_eq!(eval("-7 / 2"), Ok(Ok(-3)));
+    assert_eq!(eval("3--2"), Ok(Ok(5)));
+    assert_eq!(eval("-2 ^ 2"), Ok(Ok(4)));
+    assert_eq!(eval("(-170141183460469231731687303715884105728)"), Ok(Ok(i128::MIN)));
+    assert_eq!(eval("170141183460469231731687303715884105728"), Err(Ok(CalculatorError::Overflow)));
+
+    // Expresiones mal formadas
+    let malformed = Err(Ok(CalculatorError::MalformedExpression));
+    for expr in ["", "   ", "1 +", "* 2", "1 2", "(1 + 2", "1 + 2)", "()", "2 (3)", "-(1)", "1 & 2"] {
+        assert_eq!(eval(expr), malformed, "{}", expr);
+    }
+
+    // Errores de las operaciones
+    assert_eq!(eval("1 / (2 - 2)"), Err(Ok(CalculatorError::DivisionByZero)));
+    assert_eq!(eval("2 ^ -1"), Err(Ok(CalculatorError::NegativeExponent)));
+
+    // Límites: paréntesis anidados y longitud de la expresión
+    let nested = std::format!("{}1{}", "(".repeat(32), ")".repeat(32));
+    assert_eq!(eval(&nested), Ok(Ok(1)));
+    let nested = std::format!("{}1{}", "(".repeat(33), ")".repeat(33));
+    assert_eq!(eval(&nested), Err(Ok(CalculatorError::StackTooDeep)));
+    assert_eq!(eval(&"1".repeat(257)), Err(Ok(CalculatorError::ExpressionTooLong)));
+
+    // Límite de tokens: 64 unos sumados son 127 tokens RPN, 65 son 129.
+    // to_rpn() y evaluate() rechazan exactamente las mismas expresiones
+    let sum = |count: usize| String::from_str(&env, &std::vec!["1"; count].join("+"));
+    let tokens = client.to_rpn(&sum(64));
+    assert_eq!(tokens.len(), 127);
+    assert_eq!(client.evaluate(&tokens), 64);
+    assert_eq!(client.try_to_rpn(&sum(65)), Err(Ok(CalculatorError::ExpressionTooLong)));
+    assert_eq!(client.try_evaluate_infix(&sum(65)), Err(Ok(CalculatorError::ExpressionTooLong)));
+}
+
+// Prueba para las funciones estadísticas
+#[test]
+fn test_statistics() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+
+    let values = vec![&env, 2, 4, 4, 4, 5, 5, 7, 9];
+    assert_eq!(client.sum(&values), 40);
+    assert_eq!(client.mean(&values), 5);
+    assert_eq!(client.median(&values), 4);
+    assert_eq!(client.min(&values), 2);
+    assert_eq!(client.max(&values), 9);
+    assert_eq!(client.variance(&values), 4);
+    assert_eq!(client.std_dev(&values), 2);
+
+    // Resultados no enteros se truncan hacia cero
+    assert_eq!(client.mean(&vec![&env, 1, 2, 4]), 2);
+    assert_eq!(client.mean(&vec![&env, -1, -2, -4]), -2);
+    assert_eq!(client.median(&vec![&env, 7, 1, 3, 10]), 5);
+    assert_eq!(client.median(&vec![&env, -4, -1]), -2);
+    assert_eq!(client.median(&vec![&env, 3, -1, 2]), 2);
+
+    // La varianza usa el promedio exacto: [1, 2, 3, 4] tiene μ = 2.5 y varianza 1.25
+    assert_eq!(client.variance(&vec![&env, 1, 2, 3, 4]), 1);
+    assert_eq!(client.variance(&vec![&env, 0, 0, 1]), 0);
+    assert_eq!(client.variance(&vec![&env, 0, 10]), 25);
+    assert_eq!(client.std_dev(&vec![&env, 0, 10]), 5);
+    assert_eq!(client.variance(&vec![&env, -7]), 0);
+    assert_eq!(client.std_dev(&vec![&env, 1, 2, 3, 4, 5, 6, 7, 8, 9]), 2);
+
+    // Valores extremos: la mediana no se desborda, la suma sí
+    let extremes = vec![&env, i128::MAX, i128::MAX - 1];
+    assert_eq!(client.median(&extremes), i128::MAX - 1);
+    assert_eq!(client.median(&vec![&env, i128::MIN, i128::MAX]), 0);
+    assert_eq!(client.min(&vec![&env, i128::MIN, i128::MAX]), i128::MIN);
+    assert_eq!(client.try_sum(&extremes), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_mean(&extremes), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_variance(&vec![&env, i128::MIN, i128::MAX]), Err(Ok(CalculatorError::Overflow)));
+    assert_eq!(client.try_variance(&vec![&env, 1 << 64, 0]), Err(Ok(CalculatorError::Overflow)));
+
+    // Lista vacía
+    let empty: Vec<i128> = vec![&env];
+    assert_eq!(client.try_sum(&empty), Err(Ok(CalculatorError::EmptyInput)));
+    assert_eq!(client.try_mean(&empty), Err(Ok(CalculatorError::EmptyInput)));
+    assert_eq!(client.try_median(&empty), Err(Ok(CalculatorError::EmptyInput)));
+    assert_eq!(client.try_min(&empty), Err(Ok(CalculatorError::EmptyInput)));
+    assert_eq!(client.try_max(&empty), Err(Ok(CalculatorError::EmptyInput)));
+    assert_eq!(client.try_variance(&empty), Err(Ok(CalculatorError::EmptyInput)));
+    assert_eq!(client.try_std_dev(&empty), Err(Ok(CalculatorError::EmptyInput)));
+
+    // Hasta 100 valores
+    let mut values = vec![&env];
+    for value in 1..=100 {
+        values.push_back(value);
+    }
+    assert_eq!(client.sum(&values), 5050);
+    assert_eq!(client.median(&values), 50);
+    values.push_back(101);
+    assert_eq!(client.try_sum(&values), Err(Ok(CalculatorError::TooManyValues)));
+    assert_eq!(client.try_median(&values), Err(Ok(CalculatorError::TooManyValues)));
+    assert_eq!(client.try_max(&values), Err(Ok(CalculatorError::TooManyValues)));
+}
+
+// Prueba para isqrt() e isqrt_i128()
+#[test]
+fn test_isqrt() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+
+    assert_eq!(client.isqrt(&0), 0);
+    assert_eq!(client.isqrt(&1), 1);
+    assert_eq!(client.isqrt(&2), 1);
+
+    // Cuadrados perfectos y el valor justo debajo de cada uno
+    for root in [2u128, 3, 10, 1_000_000_007, 1 << 32, (1 << 63) + 1, u64::MAX as u128] {
+        assert_eq!(client.isqrt(&(root * root)), root);
+        assert_eq!(client.isqrt(&(root * root - 1)), root - 1);
+        assert_eq!(client.isqrt(&(root * root + 1)), root);
+    }
+
+    // Mayores valores representables
+    assert_eq!(client.isqrt(&u128::MAX), u64::MAX as u128);
+    assert_eq!(client.isqrt_i128(&i128::MAX), 13_043_817_825_332_782_212);
+    assert_eq!(client.isqrt_i128(&144), 12);
+    assert_eq!(client.isqrt_i128(&143), 11);
+    assert_eq!(client.isqrt_i128(&0), 0);
+
+    // Raíz de un negativo
+    assert_eq!(client.try_isqrt_i128(&-1), Err(Ok(CalculatorError::OutOfDomain)));
+    assert_eq!(client.try_isqrt_i128(&i128::MIN), Err(Ok(CalculatorError::OutOfDomain)));
+}
+
+// Prueba para ilog2() e ilog10() con u128 e i128
+#[test]
+fn test_ilog() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+
+    // Potencias exactas y el valor justo debajo de cada una
+    assert_eq!(client.ilog2(&1), 0);
+    for exp in 1..128u32 {
+        assert_eq!(client.ilog2(&(1u128 << exp)), exp);
+        assert_eq!(client.ilog2(&((1u128 << exp) - 1)), exp - 1);
+    }
+    assert_eq!(client.ilog10(&1), 0);
+    for exp in 1..=38u32 {
+        assert_eq!(client.ilog10(&10u128.pow(exp)), exp);
+        assert_eq!(client.ilog10(&(10u128.pow(exp) - 1)), exp - 1);
+    }
+
+    // Mayores valores representables
+    assert_eq!(client.ilog2(&u128::MAX), 127);
+    assert_eq!(client.ilog10(&u128::MAX), 38);
+    assert_eq!(client.ilog2_i128(&i128::MAX), 126);
+    assert_eq!(client.ilog10_i128(&i128::MAX), 38);
+    assert_eq!(client.ilog10_i128(&99), 1);
+    assert_eq!(client.ilog2_i128(&64), 6);
+
+    // Logaritmo de cero o de un negativo
+    assert_eq!(client.try_ilog2(&0), Err(Ok(CalculatorError::OutOfDomain)));
+    assert_eq!(client.try_ilog10(&0), Err(Ok(CalculatorError::OutOfDomain)));
+    assert_eq!(client.try_ilog2_i128(&0), Err(Ok(CalculatorError::OutOfDomain)));
+    assert_eq!(client.try_ilog10_i128(&-10), Err(Ok(CalculatorError::OutOfDomain)));
+}
+
+// Prueba para nth_root() y nth_root_i128()
+#[test]
+fn test_nth_root() {
+    let env = Env::default();
+    let contract_id = env.register(CalculatorContract, ());
+    let client = CalculatorContractClient::new(&env, &contract_id);
+
+    // Potencias perfectas y el valor justo debajo de cada una
+    for (root, k) in [(3u128, 3u32), (10, 4), (7, 20), (1_000_000_000_000, 3), (2, 127)] {
+        let power = root.pow(k);
+        assert_eq!(client.nth_root(&power, &k), root);
+        assert_eq!(client.nth_root(&(power - 1), &k), root - 1);
+    }
+    assert_eq!(client.nth_root(&0, &5), 0);
+    assert_eq!(client.nth_root(&1, &5), 1);
+    assert_eq!(client.nth_root(&12345, &1), 12345);
+    assert_eq!(client.nth_root(&25, &2), client.isqrt(&25));
+
+    // Mayores valores representables
+    assert_eq!(client.nth_root(&u128::MAX, &1), u128::MAX);
+    assert_eq!(client.nth_root(&u128::MAX, &2), u64::MAX as u128);
+    assert_eq!(client.nth_root(&u128::MAX, &3), 6_981_463_658_331);
+    assert_eq!(client.nth_root(&u128::MAX, &127), 2);
+    assert_eq!(client.nth_root(&u128::MAX, &128), 1);
+    assert_eq!(client.nth_root(&u128::MAX, &u32::MAX), 1);
+
+    // Con signo: raíces impares de negativos, truncadas hacia cero
+    assert_eq!(client.nth_root_i128(&-27, &3), -3);
+    assert_eq!(client.nth_root_i128(&-26, &3), -2);
+    assert_eq!(client.nth_root_i128(&26, &3), 2);
+    assert_eq!(client.nth_root_i128(&i128::MIN, &127), -2);
+    assert_eq!(client.nth_root_i128(&i128::MIN, &1), i128::MIN);
+    assert_eq!(client.nth_root_i128(&i128::MAX, &2), 13_043_817_825_332_782_212);
+    assert_eq!(client.nth_root_i128(&i128::MAX, &127), 1);
+
+    // Índice 0 y raíces pares de negativos
+    assert_eq!(client.try_nth_root(&8, &0), Err(Ok(CalculatorError::OutOfDomain)));
+    assert_eq!(client.try_nth_root_i128(&8, &0), Err(Ok(CalculatorError::OutOfDomain)));
+    assert_eq!(client.try_nth_root_i128(&-4, &2), Err(Ok(CalculatorError::OutOfDomain)));
 }
@@ -0,0 +1,107 @@
+#![no_main]
+
+// =============================================================================
+// FUZZING DE LIBRARYCONTRACT
+// libFuzzer genera una secuencia de operaciones con títulos, autores y estados
+// aleatorios (vía `arbitrary` de soroban-sdk). Un LibraryError es un resultado
+// esperado; cualquier otro panic o una invariante rota cuenta como hallazgo
+// =============================================================================
+
+use libfuzzer_sys::fuzz_target;
+use library::{BookStatus, LibraryContract, LibraryContractClient, LibraryError};
+use soroban_sdk::{
+    testutils::{
+        arbitrary::{arbitrary, Arbitrary, SorobanArbitrary},
+        Address as _, EnvTestConfig,
+    },
+    Address, Env, IntoVal, String,
+};
+
+// Miembros que participan y cantidad de IDs de libro que se usan
+// (los IDs que aún no existen prueban BookNotFound)
+const MEMBERS: usize = 3;
+const BOOK_IDS: u8 = 8;
+
+/// Operación generada por el fuzzer
+#[derive(Arbitrary, Debug)]
+enum Op {
+    AddBook {
+        title: <String as SorobanArbitrary>::Prototype,
+        author: <String as SorobanArbitrary>::Prototype,
+    },
+    Borrow { member: u8, book_id: u8 },
+    Return { member: u8, book_id: u8 },
+    Reserve { member: u8, book_id: u8 },
+    ChangeStatus {
+        book_id: u8,
+        status: <BookStatus as SorobanArbitrary>::Prototype,
+    },
+}
+
+/// Acepta un LibraryError como resultado esperado y entra en pánico con cualquier
+/// otra falla (un panic del contrato o un valor que no se pudo convertir)
+fn expect_library_result<T, C: core::fmt::Debug, I: core::fmt::Debug>(
+    result: Result<Result<T, C>, Result<LibraryError, I>>,
+) {
+    match result {
+        Ok(value) => {
+            value.expect("el valor de retorno no se pudo convertir");
+        }
+        Err(error) => {
+            error.expect("el contrato falló sin un LibraryError");
+        }
+    }
+}
+
+fuzz_target!(|ops: std::vec::Vec<Op>| {
+    // Sin snapshots: cada ejecución del fuzzer escribiría un archivo
+    let env = Env::new_with_config(EnvTestConfig {
+        capture_snapshot_at_drop: false,
+    });
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let librarian = Address::generate(&env);
+    client.initialize(&librarian);
+    let members: std::vec::Vec<Address> = (0..MEMBERS).map(|_| Address::generate(&env)).collect();
+    let member = |index: u8| &members[index as usize % MEMBERS];
+    let book = |book_id: u8| (book_id % BOOK_IDS) as u32;
+
+    for op in ops {
+        match op {
+            Op::AddBook { title, author } => {
+                let title: String = title.into_val(&env);
+                let author: String = author.into_val(&env);
+                expect_library_result(client.try_add_book(&title, &author));
+            }
+            Op::Borrow { member: m, book_id } => {
+                expect_library_result(client.try_borrow_book(member(m), &book(book_id)));
+            }
+            Op::Return { member: m, book_id } => {
+                expect_library_result(client.try_return_book(member(m), &book(book_id)));
+            }
+            Op::Reserve { member: m, book_id } => {
+                expect_library_result(client.try_reserve_book(member(m), &book(book_id)));
+            }
+            Op::ChangeStatus { book_id, status } => {
+                let status: BookStatus = status.into_val(&env);
+                let book_id = book(book_id);
+                expect_library_result(client.try_change_book_status(&librarian, &book_id, &status));
+            }
+        }
+
+        // 🔍 INVARIANTES: Los disponibles son exactamente los libros con estado Available
+        // y los préstamos activos suman la cantidad de libros con prestatario
+        let all_books = client.get_all_books();
+        let available = client.get_available_books(&None);
+        let expected = all_books
+            .iter()
+            .filter(|book| book.status == BookStatus::Available)
+            .count();
+        assert_eq!(available.len() as usize, expected);
+
+        let loans: u32 = members.iter().map(|m| client.get_active_loans(m)).sum();
+        let borrowed = all_books.iter().filter(|book| book.borrower.is_some()).count();
+        assert_eq!(loans as usize, borrowed);
+    }
+});
@@ -3,7 +3,11 @@
 #![no_std]
 
 // Importamos los tipos y macros necesarios del SDK de Soroban
-use soroban_sdk::{contract, contractimpl, contracterror, contracttype, Env, String, Symbol, Vec, symbol_short};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, contracterror, contracttype, symbol_short, token, vec, xdr::ToXdr,
+    Address, BytesN, Env, IntoVal, String, Symbol, TryFromVal, Val, Vec,
+};
 
 // =============================================================================
 // DEFINICIÓN DE TIPOS DE DATOS
@@ -17,6 +21,7 @@ pub enum BookStatus {
     Available,   // El libro está disponible para préstamo
     Borrowed,    // El libro está actualmente prestado
     Reserved,    // El libro está reservado
+    InTransit,   // El libro viaja entre dos sedes
 }
 
 /// Struct que representa un libro en nuestro sistema de biblioteca
@@ -28,6 +33,42 @@ pub struct Book {
     pub title: String,  // Título del libro
     pub author: String, // Autor del libro
     pub status: BookStatus, // Estado actual del libro
+    pub restricted: bool,   // Si es true, el préstamo requiere aprobación de un bibliotecario
+    pub home_branch: u32,   // Sede a la que pertenece el ejemplar
+    pub current_branch: u32, // Sede donde se encuentra el ejemplar ahora
+    pub in_transit_to: Option<u32>, // Sede de destino mientras el libro está en tránsito
+    pub borrower: Option<Address>,  // Quién tiene el libro prestado (un miembro u otra biblioteca)
+    pub owner: Option<Address>,     // Dueño del ejemplar en modo NFT (None = libro de la biblioteca)
+    pub donor: Option<Address>,     // Miembro que donó el libro, si fue una donación
+}
+
+/// Struct que representa una sede (sucursal) de la biblioteca
+/// Cada sede tiene su propio administrador
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Branch {
+    pub id: u32,         // Identificador único de la sede
+    pub name: String,    // Nombre de la sede
+    pub admin: Address,  // Administrador de la sede
+}
+
+/// Enum con los estados de una solicitud de préstamo de un libro restringido
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RequestStatus {
+    Pending,   // Esperando la decisión de un bibliotecario
+    Approved,  // Aprobada: el libro quedó prestado al miembro
+    Rejected,  // Rechazada por un bibliotecario
+}
+
+/// Struct que representa una solicitud de préstamo de un libro restringido
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BorrowRequest {
+    pub id: u32,               // Identificador único de la solicitud
+    pub book_id: u32,          // Libro solicitado
+    pub member: Address,       // Miembro que solicita el préstamo
+    pub status: RequestStatus, // Estado actual de la solicitud
 }
 
 /// Enum para manejar errores personalizados del contrato
@@ -39,6 +80,205 @@ pub enum LibraryError {
     BookNotFound = 1,     // Cuando no se encuentra un libro por su ID
     BookNotAvailable = 2, // Cuando el libro no está disponible para una operación
     InvalidBookData = 3,  // Cuando los datos del libro son inválidos (vacíos)
+    NotBorrowedByMember = 4, // Cuando el miembro nunca tomó prestado el libro que quiere calificar
+    InvalidRating = 5,       // Cuando las estrellas o el comentario de una reseña son inválidos
+    AlreadyRated = 6,        // Cuando el miembro ya calificó ese libro
+    NotInitialized = 7,        // Cuando la biblioteca todavía no tiene administrador
+    AlreadyInitialized = 8,    // Cuando se intenta inicializar la biblioteca dos veces
+    Unauthorized = 9,          // Cuando quien llama no tiene permisos para la operación
+    RequestNotFound = 10,      // Cuando no existe la solicitud de préstamo
+    RequestNotPending = 11,    // Cuando la solicitud ya fue aprobada o rechazada
+    RequestAlreadyPending = 12, // Cuando el miembro ya tiene una solicitud pendiente para el libro
+    BranchNotFound = 13,       // Cuando no existe la sede indicada
+    InvalidTransfer = 14,      // Cuando el traslado no es válido (misma sede u origen incorrecto)
+    PartnerLoanNotFound = 15,  // Cuando no hay un préstamo vigente con esa biblioteca asociada
+    BookNotOwned = 16,         // Cuando el libro no tiene dueño (no está en modo NFT)
+    BookOnLoan = 17,           // Cuando se intenta transferir un libro que está prestado
+    MembershipExpired = 18,    // Cuando la membresía del miembro venció (o nunca existió)
+    MembershipNotConfigured = 19, // Cuando todavía no hay una configuración de tarifas
+    InvalidConfig = 20,        // Cuando los valores de configuración están fuera de rango
+    InvalidPeriod = 21,        // Cuando la cantidad de períodos a pagar no es válida
+    DonationNotFound = 22,     // Cuando no existe la donación
+    DonationNotPending = 23,   // Cuando la donación ya fue aceptada o rechazada
+    CouncilRequired = 24,      // Cuando hay consejo y la operación debe hacerse con una propuesta
+    InvalidCouncil = 25,       // Cuando los miembros o el umbral del consejo no son válidos
+    ProposalNotFound = 26,     // Cuando no existe la propuesta
+    ProposalExpired = 27,      // Cuando la propuesta venció sin alcanzar el umbral
+    ProposalAlreadyExecuted = 28, // Cuando la propuesta ya se ejecutó
+    AlreadyApproved = 29,      // Cuando el miembro del consejo ya aprobó la propuesta
+    LoanLimitReached = 30,     // Cuando el miembro (o su delegado) llegó al máximo de préstamos
+    DelegateLimitReached = 31, // Cuando se superan los delegados o dependientes permitidos
+    KioskNotFound = 32,        // Cuando la llave del kiosco no está registrada
+    InvalidNonce = 33,         // Cuando el nonce no es el próximo esperado (posible repetición)
+    DepositsLocked = 34,       // Cuando un retiro o un cambio de token tocaría los depósitos de los miembros
+}
+
+/// Struct con el consejo de administración M-de-N
+/// Las operaciones sensibles se ejecutan cuando `threshold` miembros las aprueban
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Council {
+    pub members: Vec<Address>,  // Miembros del consejo (N)
+    pub threshold: u32,         // Aprobaciones necesarias (M)
+}
+
+/// Enum con las operaciones sensibles que puede proponer el consejo
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdminAction {
+    SetConfig(LibraryConfig),         // Cambiar la tabla de tarifas
+    AddLibrarian(Address),            // Dar permisos de bibliotecario
+    RemoveLibrarian(Address),         // Quitar permisos de bibliotecario
+    AddBranch(String, Address),       // Abrir una sede (nombre, administrador)
+    SetBranchAdmin(u32, Address),     // Cambiar el administrador de una sede
+    Withdraw(Address, i128),          // Retirar fondos de la tesorería (destino, monto)
+    SetCouncil(Vec<Address>, u32),    // Cambiar los miembros y el umbral del consejo
+    AddKiosk(BytesN<32>),             // Registrar la llave pública de un kiosco
+    RemoveKiosk(BytesN<32>),          // Dar de baja un kiosco
+    AddBook(u32, String, String),     // Agregar un libro al catálogo (sede, título, autor)
+    SetBookRestricted(u32, bool),     // Marcar o desmarcar un libro como restringido
+}
+
+/// Struct que representa una propuesta del consejo
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub id: u32,                  // Identificador único de la propuesta
+    pub action: AdminAction,      // Operación propuesta
+    pub proposer: Address,        // Miembro del consejo que la propuso
+    pub approvals: Vec<Address>,  // Miembros que la aprobaron (incluye al proponente)
+    pub expires_at: u64,          // Momento (timestamp del ledger) en que vence
+    pub executed: bool,           // Si ya se ejecutó
+}
+
+/// Struct con el préstamo que un kiosco de autoservicio firma off-chain
+/// La firma ed25519 cubre (dirección del contrato, payload) en formato XDR
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KioskPayload {
+    pub kiosk: BytesN<32>,        // Llave pública ed25519 del kiosco
+    pub member: Address,          // Miembro que retira el libro
+    pub book_id: u32,             // Libro a prestar
+    pub nonce: u64,               // Debe coincidir con el próximo nonce del kiosco
+}
+
+/// Enum con los estados de una donación propuesta por un miembro
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DonationStatus {
+    Pending,   // Esperando la decisión de un bibliotecario
+    Accepted,  // Aceptada: ya es un libro del catálogo
+    Rejected,  // Rechazada por un bibliotecario
+}
+
+/// Struct que representa un libro que un miembro ofrece donar
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Donation {
+    pub id: u32,                // Identificador único de la donación
+    pub donor: Address,         // Miembro que dona el libro
+    pub title: String,          // Título del libro ofrecido
+    pub author: String,         // Autor del libro ofrecido
+    pub status: DonationStatus, // Estado actual de la donación
+    pub book_id: Option<u32>,   // ID del libro en el catálogo, una vez aceptada
+}
+
+/// Struct con la tabla de tarifas de la biblioteca, guardada on-chain
+/// Todos los montos se expresan en unidades del token configurado
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LibraryConfig {
+    pub token: Address,           // Token con el que se cobran tarifas y membresías
+    pub late_fee_per_day: i128,   // Multa por cada día de atraso en una devolución
+    pub deposit: i128,            // Depósito de garantía por préstamo
+    pub replacement_cost: i128,   // Costo de reposición de un libro perdido
+    pub membership_price: i128,   // Precio de un período de membresía
+    pub membership_period: u64,   // Duración de un período de membresía en segundos
+    pub max_loans: u32,           // Préstamos activos por miembro (incluye los de sus dependientes)
+}
+
+/// Struct con la membresía de un miembro
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Membership {
+    pub member: Address,  // Miembro dueño de la membresía
+    pub expires_at: u64,  // Momento (timestamp del ledger) en que vence
+}
+
+/// Struct con la reserva temporal de un libro
+/// Vive en temporary storage: al vencer su TTL desaparece sola y el libro
+/// vuelve a estar disponible sin transacciones de limpieza
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Hold {
+    pub member: Address,  // Miembro que reservó el libro
+    pub expires_at: u32,  // Ledger (número de secuencia) en que vence la reserva
+}
+
+/// Struct con un préstamo activo y su fecha de devolución
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Loan {
+    pub member: Address,  // Miembro a cuyo nombre está el préstamo
+    pub due_at: u64,      // Momento (timestamp del ledger) en que vence el préstamo
+    pub reminded: bool,   // Si ya se emitió el recordatorio de atraso
+    pub deposit: i128,    // Depósito cobrado al prestar (se liquida al devolver)
+    pub payer: Option<Address>, // Quién dejó el depósito y recibe el reintegro
+}
+
+/// Struct con la reseña que un miembro deja sobre un libro
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Review {
+    pub member: Address,  // Miembro que escribió la reseña
+    pub stars: u32,       // Calificación de 1 a 5 estrellas
+    pub comment: String,  // Comentario libre del miembro
+}
+
+/// Struct con el resumen agregado de calificaciones de un libro
+/// El promedio se guarda multiplicado por 100 porque no usamos decimales
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RatingSummary {
+    pub count: u32,         // Cantidad de reseñas recibidas
+    pub total_stars: u32,   // Suma de todas las estrellas
+    pub average_x100: u32,  // Promedio de estrellas × 100 (ej: 450 = 4.50 estrellas)
+}
+
+/// Struct que devuelve un libro junto con su resumen de calificaciones
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BookDetails {
+    pub book: Book,             // Datos del libro
+    pub rating: RatingSummary,  // Resumen de calificaciones del libro
+}
+
+/// Enum con las claves de storage que dependen de más de un dato
+/// (los libros se siguen guardando directamente con su ID como clave)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    HasBorrowed(Address, u32),  // Marca que un miembro tomó prestado un libro alguna vez
+    Review(u32, Address),       // Reseña de un miembro para un libro
+    Rating(u32),                // Resumen de calificaciones de un libro
+    Librarian(Address),         // Marca a una dirección como bibliotecario
+    Request(u32),               // Solicitud de préstamo por su ID
+    PendingRequest(Address, u32), // Solicitud pendiente de un miembro para un libro
+    Branch(u32),                // Sede por su ID
+    PartnerLoan(Address, u32),  // Libro que esta biblioteca tomó prestado de otra biblioteca
+    Approved(u32),              // Dirección autorizada a transferir un libro con dueño
+    Balance(Address),           // Cantidad de libros con dueño que tiene una dirección
+    Membership(Address),        // Membresía de un miembro
+    Donation(u32),              // Donación por su ID
+    DonorDonations(Address),    // IDs de las donaciones propuestas por un miembro
+    Proposal(u32),              // Propuesta del consejo por su ID
+    ActiveLoans(Address),       // Cantidad de libros que un miembro tiene prestados ahora
+    Delegates(Address),         // Delegados autorizados a pedir libros por un miembro
+    Dependents(Address),        // Miembros por los que un delegado puede pedir libros
+    Kiosk(BytesN<32>),          // Próximo nonce de un kiosco (se conserva aunque se dé de baja)
+    KioskRevoked(BytesN<32>),   // Marca de un kiosco dado de baja
+    Hold(u32),                  // Reserva temporal de un libro (temporary storage)
+    Loan(u32),                  // Préstamo activo de un libro por su ID
 }
 
 // =============================================================================
@@ -53,6 +293,63 @@ pub struct LibraryContract;  // Nuestro contrato principal (sin campos)
 // symbol_short! crea un Symbol eficiente para claves de storage
 const NEXT_ID_KEY: Symbol = symbol_short!("next_id");
 
+// Claves para el administrador y el contador de solicitudes de préstamo
+const ADMIN_KEY: Symbol = symbol_short!("admin");
+const NEXT_REQUEST_KEY: Symbol = symbol_short!("next_req");
+
+// Sede principal (creada al inicializar) y contador de sedes adicionales
+const MAIN_BRANCH_ID: u32 = 0;
+const NEXT_BRANCH_KEY: Symbol = symbol_short!("next_brch");
+
+// Clave de la configuración de tarifas; mientras no exista, la biblioteca es gratuita
+const CONFIG_KEY: Symbol = symbol_short!("config");
+
+// Total de depósitos de garantía en poder de la biblioteca (no se pueden retirar)
+const DEPOSITS_KEY: Symbol = symbol_short!("deposits");
+
+// Rangos válidos para la configuración
+const MAX_FEE: i128 = 1_000_000_000_000_000;          // Tope para cualquier monto
+const MIN_MEMBERSHIP_PERIOD: u64 = 24 * 60 * 60;       // 1 día
+const MAX_MEMBERSHIP_PERIOD: u64 = 366 * 24 * 60 * 60; // 1 año
+const MAX_LOANS_LIMIT: u32 = 50;                       // Tope para max_loans
+
+// Límite de préstamos activos mientras no haya configuración
+const DEFAULT_MAX_LOANS: u32 = 5;
+
+// Límites de delegados por miembro y de dependientes por delegado
+const MAX_DELEGATES: u32 = 5;
+const MAX_DEPENDENTS: u32 = 40;
+
+// Duración de una reserva en ledgers (~1 día con ledgers de 5 segundos)
+// También es el TTL de la entrada en temporary storage
+const HOLD_LEDGERS: u32 = 17_280;
+
+// Plazo de un préstamo y recorrido de sweep_overdue()
+const LOAN_PERIOD: u64 = 14 * 24 * 60 * 60;      // 2 semanas
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;       // Unidad de la multa por atraso
+const SWEEP_CURSOR_KEY: Symbol = symbol_short!("sweep");
+const MAX_SWEEP_BATCH: u32 = 50;                 // Libros revisados por llamada como máximo
+
+// Contador de donaciones propuestas
+const NEXT_DONATION_KEY: Symbol = symbol_short!("next_don");
+
+// Consejo de administración y sus propuestas
+const COUNCIL_KEY: Symbol = symbol_short!("council");
+const NEXT_PROPOSAL_KEY: Symbol = symbol_short!("next_prop");
+const MAX_COUNCIL_SIZE: u32 = 10;
+const PROPOSAL_LIFETIME: u64 = 7 * 24 * 60 * 60; // Las propuestas vencen a los 7 días
+
+// Límites para las reseñas de libros
+const MIN_STARS: u32 = 1;
+const MAX_STARS: u32 = 5;
+const MAX_COMMENT_LEN: u32 = 280;
+
+// Vida de los datos de cada miembro en persistent storage (~1 día = 17_280 ledgers)
+// Cada lectura o escritura renueva el TTL cuando baja del umbral
+const DAY_IN_LEDGERS: u32 = 17_280;
+const TTL_THRESHOLD: u32 = 7 * DAY_IN_LEDGERS;  // Renovar si quedan menos de 7 días
+const TTL_EXTEND: u32 = 30 * DAY_IN_LEDGERS;    // Renovar hasta 30 días
+
 // =============================================================================
 // IMPLEMENTACIÓN DEL CONTRATO
 // =============================================================================
@@ -61,8 +358,88 @@ const NEXT_ID_KEY: Symbol = symbol_short!("next_id");
 #[contractimpl]
 impl LibraryContract {
 
+    /// 🏁 INICIALIZAR BIBLIOTECA
+    /// Registra al administrador de la biblioteca (solo puede hacerse una vez)
+    /// El administrador también actúa como bibliotecario y administra la sede principal
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - admin: Dirección del administrador
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si ya estaba inicializada
+    pub fn initialize(env: Env, admin: Address) -> Result<(), LibraryError> {
+        if env.storage().instance().has(&ADMIN_KEY) {
+            return Err(LibraryError::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+        env.storage().instance().set(&ADMIN_KEY, &admin);
+
+        // 🏢 SEDE PRINCIPAL: Los libros agregados con add_book pertenecen a ella
+        let main_branch = Branch {
+            id: MAIN_BRANCH_ID,
+            name: String::from_str(&env, "Principal"),
+            admin,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::Branch(MAIN_BRANCH_ID), &main_branch);
+
+        Ok(())
+    }
+
+    /// 👩‍💼 AGREGAR BIBLIOTECARIO
+    /// Permite al administrador dar permisos de bibliotecario a una dirección
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - librarian: Dirección del nuevo bibliotecario
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si no hay administrador
+    pub fn add_librarian(env: Env, librarian: Address) -> Result<(), LibraryError> {
+        Self::require_admin(&env)?;
+
+        Self::apply_add_librarian(&env, librarian)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: AGREGAR BIBLIOTECARIO
+    /// Aplica el cambio sin verificar permisos (lo hacen add_librarian() y el consejo)
+    fn apply_add_librarian(env: &Env, librarian: Address) -> Result<(), LibraryError> {
+        env.storage()
+            .instance()
+            .set(&DataKey::Librarian(librarian), &true);
+
+        Ok(())
+    }
+
+    /// ❌ QUITAR BIBLIOTECARIO
+    /// Permite al administrador retirar los permisos de un bibliotecario
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - librarian: Dirección del bibliotecario a quitar
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si no hay administrador
+    pub fn remove_librarian(env: Env, librarian: Address) -> Result<(), LibraryError> {
+        Self::require_admin(&env)?;
+
+        Self::apply_remove_librarian(&env, librarian)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: QUITAR BIBLIOTECARIO
+    /// Aplica el cambio sin verificar permisos (lo hacen remove_librarian() y el consejo)
+    fn apply_remove_librarian(env: &Env, librarian: Address) -> Result<(), LibraryError> {
+        env.storage()
+            .instance()
+            .remove(&DataKey::Librarian(librarian));
+
+        Ok(())
+    }
+
     /// 🆕 AGREGAR NUEVO LIBRO
-    /// Crea un nuevo libro en la biblioteca con estado "Available"
+    /// Crea un nuevo libro en la sede principal con estado "Available"
+    /// Es add_book_to_branch sobre la sede principal: firma su administrador
+    /// (con consejo, el catálogo solo cambia mediante AdminAction::AddBook)
     /// 
     /// Parámetros:
     /// - env: El entorno de ejecución de Soroban (proporcionado automáticamente)
@@ -71,13 +448,50 @@ impl LibraryContract {
     /// 
     /// Retorna: Result<u32, LibraryError> - El ID del nuevo libro o un error
     pub fn add_book(env: Env, title: String, author: String) -> Result<u32, LibraryError> {
+        Self::add_book_to_branch(env, MAIN_BRANCH_ID, title, author)
+    }
+
+    /// 🏢 AGREGAR LIBRO A UNA SEDE
+    /// Crea un nuevo ejemplar cuya sede de origen es la indicada
+    /// Solo el administrador de esa sede puede agregar libros a ella
+    /// (con consejo, solo mediante AdminAction::AddBook)
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - branch_id: ID de la sede de origen del ejemplar
+    /// - title: Título del libro
+    /// - author: Autor del libro
+    /// 
+    /// Retorna: Result<u32, LibraryError> - El ID del nuevo libro o un error
+    pub fn add_book_to_branch(
+        env: Env,
+        branch_id: u32,
+        title: String,
+        author: String,
+    ) -> Result<u32, LibraryError> {
+        Self::require_no_council(&env)?;
+
+        let branch = Self::get_branch(env.clone(), branch_id).ok_or(LibraryError::BranchNotFound)?;
+        branch.admin.require_auth();
+
+        Self::create_book(&env, title, author, branch_id)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: CREAR LIBRO
+    /// Valida los datos y guarda un nuevo ejemplar ubicado en su sede de origen
+    fn create_book(
+        env: &Env,
+        title: String,
+        author: String,
+        branch_id: u32,
+    ) -> Result<u32, LibraryError> {
         // 🔍 VALIDACIÓN: Verificamos que los datos no estén vacíos
-        if title.len() == 0 || author.len() == 0 {
+        if title.is_empty() || author.is_empty() {
             return Err(LibraryError::InvalidBookData);
         }
 
         // 📊 OBTENER PRÓXIMO ID: Usamos nuestra función helper
-        let next_id = Self::get_next_book_id(&env);
+        let next_id = Self::get_next_book_id(env);
 
         // 📖 CREAR NUEVO LIBRO: Construimos la estructura Book
         let new_book = Book {
@@ -85,6 +499,13 @@ impl LibraryContract {
             title: title.clone(),           // Clonamos el título (String es dueño de sus datos)
             author: author.clone(),         // Clonamos el autor
             status: BookStatus::Available,  // Estado inicial: Disponible
+            restricted: false,              // Por defecto se puede prestar sin aprobación
+            home_branch: branch_id,         // Sede de origen del ejemplar
+            current_branch: branch_id,      // Inicialmente está en su sede de origen
+            in_transit_to: None,            // No está viajando a ninguna sede
+            borrower: None,                 // Nadie lo tiene prestado
+            owner: None,                    // Pertenece a la biblioteca
+            donor: None,                    // No es una donación
         };
 
         // 💾 GUARDAR EN STORAGE: Usamos instance storage (datos persistentes del contrato)
@@ -109,23 +530,38 @@ impl LibraryContract {
     pub fn get_book(env: Env, book_id: u32) -> Option<Book> {
         // 📚 BUSCAR EN STORAGE: Intentamos obtener el libro del storage
         // Si no existe, retorna None automáticamente
-        env.storage().instance().get(&book_id)
+        let mut book: Book = env.storage().instance().get(&book_id)?;
+
+        // 🔒 RESERVA: Un libro disponible con una reserva vigente se muestra como reservado
+        if book.status == BookStatus::Available && Self::get_hold(env.clone(), book_id).is_some() {
+            book.status = BookStatus::Reserved;
+        }
+
+        Some(book)
     }
 
     /// 🔄 CAMBIAR ESTADO DE LIBRO
-    /// Función interna para actualizar el estado de un libro
+    /// Permite a un bibliotecario marcar a mano un libro de la biblioteca como
+    /// disponible o reservado. Los préstamos (lend_book / return_book) y los
+    /// traslados (transfer_book / receive_book) tienen sus propias funciones
     /// 
     /// Parámetros:
     /// - env: Entorno de Soroban
+    /// - librarian: Bibliotecario o administrador (debe firmar)
     /// - book_id: ID del libro a modificar
     /// - new_status: Nuevo estado a asignar
     /// 
     /// Retorna: Result<(), LibraryError> - Éxito o error si el libro no existe
+    /// o el cambio no está permitido
     pub fn change_book_status(
         env: Env,
+        librarian: Address,
         book_id: u32,
         new_status: BookStatus,
     ) -> Result<(), LibraryError> {
+        // 🔐 AUTORIZACIÓN: Solo un bibliotecario puede cambiar estados a mano
+        Self::require_librarian(&env, &librarian)?;
+
         // 📖 OBTENER LIBRO ACTUAL: Buscamos el libro o retornamos error
         // ok_or convierte Option<> en Result<> con nuestro error personalizado
         let mut book: Book = env
@@ -134,6 +570,20 @@ impl LibraryContract {
             .get(&book_id)
             .ok_or(LibraryError::BookNotFound)?;
 
+        // 👤 LIBROS CON DUEÑO: Su estado lo maneja el dueño con lend_owned_book
+        if book.owner.is_some() {
+            return Err(LibraryError::Unauthorized);
+        }
+
+        // 🚫 PRÉSTAMOS Y TRASLADOS: No se crean ni se terminan con esta función,
+        // así no se saltean membresía, límites, reservas ni aprobaciones
+        let managed = |status: &BookStatus| {
+            matches!(status, BookStatus::Borrowed | BookStatus::InTransit)
+        };
+        if managed(&book.status) || managed(&new_status) {
+            return Err(LibraryError::BookNotAvailable);
+        }
+
         // ✏️ ACTUALIZAR ESTADO: Modificamos el campo status
         book.status = new_status;
 
@@ -144,14 +594,55 @@ impl LibraryContract {
     }
 
     /// 📚 PRESTAR LIBRO
-    /// Permite tomar prestado un libro si está disponible
+    /// Permite a un miembro tomar prestado un libro si está disponible
+    /// Si el libro es restringido, en lugar de prestarlo se crea una solicitud
+    /// pendiente que un bibliotecario debe aprobar o rechazar
     /// 
     /// Parámetros:
     /// - env: Entorno de Soroban
+    /// - member: Dirección del miembro que toma el libro (debe firmar)
     /// - book_id: ID del libro a prestar
     /// 
     /// Retorna: Result<(), LibraryError> - Éxito o error si no está disponible
-    pub fn borrow_book(env: Env, book_id: u32) -> Result<(), LibraryError> {
+    pub fn borrow_book(env: Env, member: Address, book_id: u32) -> Result<(), LibraryError> {
+        // 🔐 AUTORIZACIÓN: El miembro debe firmar la transacción
+        member.require_auth();
+
+        Self::borrow_as(&env, member.clone(), book_id, Some(&member))
+    }
+
+    /// 👪 PRESTAR LIBRO A UN DEPENDIENTE
+    /// Un delegado (madre, padre, docente) toma prestado un libro en nombre de un
+    /// dependiente. El préstamo queda a nombre del dependiente, pero firma el delegado,
+    /// que es quien deja el depósito
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - delegate: Delegado registrado del dependiente (debe firmar)
+    /// - dependent: Miembro a cuyo nombre queda el préstamo
+    /// - book_id: ID del libro a prestar
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error
+    pub fn borrow_book_for(
+        env: Env,
+        delegate: Address,
+        dependent: Address,
+        book_id: u32,
+    ) -> Result<(), LibraryError> {
+        Self::require_delegate(&env, &delegate, &dependent)?;
+
+        Self::borrow_as(&env, dependent, book_id, Some(&delegate))
+    }
+
+    /// 🔧 FUNCIÓN HELPER: PRESTAR A NOMBRE DE UN MIEMBRO
+    /// Lógica común de borrow_book, borrow_book_for y kiosk_checkout (la autorización
+    /// ya se verificó). `payer` es quien firmó y deja el depósito (None = sin depósito)
+    fn borrow_as(
+        env: &Env,
+        member: Address,
+        book_id: u32,
+        payer: Option<&Address>,
+    ) -> Result<(), LibraryError> {
         // 📖 OBTENER LIBRO: Verificamos que existe
         let book: Book = env
             .storage()
@@ -161,9 +652,17 @@ impl LibraryContract {
 
         // ✅ VERIFICAR DISPONIBILIDAD: Usamos match para ver el estado actual
         match book.status {
+            BookStatus::Available if book.owner.is_some() => {
+                // 🪙 LIBRO CON DUEÑO: Solo el dueño puede prestarlo (lend_owned_book)
+                Err(LibraryError::Unauthorized)
+            }
+            BookStatus::Available if book.restricted => {
+                // 📨 LIBRO RESTRINGIDO: Creamos una solicitud en lugar de prestarlo
+                Self::create_borrow_request(env, member, book_id)
+            }
             BookStatus::Available => {
-                // 🔄 CAMBIAR ESTADO: Si está disponible, lo marcamos como prestado
-                Self::change_book_status(env, book_id, BookStatus::Borrowed)
+                // 🔄 PRESTAR: Si está disponible, lo marcamos como prestado
+                Self::lend_book(env, member, book_id, payer)
             }
             _ => {
                 // ❌ NO DISPONIBLE: Si no está disponible, retornamos error
@@ -173,26 +672,180 @@ impl LibraryContract {
     }
 
     /// ↩️ DEVOLVER LIBRO
-    /// Permite devolver un libro que estaba prestado
+    /// Permite al prestatario devolver un libro que estaba prestado
     /// 
     /// Parámetros:
     /// - env: Entorno de Soroban
+    /// - member: Dirección de quien tiene el libro (debe firmar)
     /// - book_id: ID del libro a devolver
     /// 
     /// Retorna: Result<(), LibraryError> - Éxito o error si no estaba prestado
-    pub fn return_book(env: Env, book_id: u32) -> Result<(), LibraryError> {
-        // 📖 OBTENER LIBRO: Verificamos que existe
+    pub fn return_book(env: Env, member: Address, book_id: u32) -> Result<(), LibraryError> {
+        // 🔐 AUTORIZACIÓN: Quien devuelve debe firmar la transacción
+        member.require_auth();
+
+        Self::return_as(&env, member.clone(), book_id, &member)
+    }
+
+    /// 👪 DEVOLVER LIBRO DE UN DEPENDIENTE
+    /// Un delegado devuelve un libro prestado a nombre de su dependiente
+    /// y paga la multa por atraso que el depósito no cubra
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - delegate: Delegado registrado del dependiente (debe firmar)
+    /// - dependent: Miembro que tiene el libro prestado
+    /// - book_id: ID del libro a devolver
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error
+    pub fn return_book_for(
+        env: Env,
+        delegate: Address,
+        dependent: Address,
+        book_id: u32,
+    ) -> Result<(), LibraryError> {
+        Self::require_delegate(&env, &delegate, &dependent)?;
+
+        Self::return_as(&env, dependent, book_id, &delegate)
+    }
+
+    /// 📕 REPORTAR LIBRO PERDIDO
+    /// El miembro que tiene prestado un libro de la biblioteca avisa que lo perdió:
+    /// paga el costo de reposición (descontando su depósito), el préstamo termina
+    /// y el libro sale del catálogo
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - member: Miembro que tiene el libro prestado (debe firmar)
+    /// - book_id: ID del libro perdido
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error
+    pub fn report_lost(env: Env, member: Address, book_id: u32) -> Result<(), LibraryError> {
+        member.require_auth();
+
         let book: Book = env
             .storage()
             .instance()
             .get(&book_id)
             .ok_or(LibraryError::BookNotFound)?;
 
+        // ✅ VERIFICAR PRÉSTAMO: Solo quien lo tiene prestado puede reportarlo
+        if book.status != BookStatus::Borrowed {
+            return Err(LibraryError::BookNotAvailable);
+        }
+        if book.borrower.as_ref() != Some(&member) {
+            return Err(LibraryError::Unauthorized);
+        }
+
+        // 👤 LIBROS CON DUEÑO: La reposición se arregla entre el dueño y el prestatario
+        if book.owner.is_some() {
+            return Err(LibraryError::Unauthorized);
+        }
+
+        // 💰 REPOSICIÓN: Se cobra el costo de reposición y el libro deja de existir
+        let charged = Self::close_loan(&env, &member, book_id, &member, |_, _, config| {
+            config.replacement_cost
+        });
+        env.storage().instance().remove(&book_id);
+
+        env.events()
+            .publish((symbol_short!("lost"), book_id), (member, charged));
+
+        Ok(())
+    }
+
+    /// 🔧 FUNCIÓN HELPER: TERMINAR PRÉSTAMO
+    /// Libera el lugar del miembro en su límite de préstamos, borra el préstamo
+    /// y liquida el depósito contra el cargo que calcula `charge`: lo que sobra
+    /// vuelve a quien dejó el depósito y la diferencia la paga `payer`, que debe
+    /// haber firmado la transacción. Sin configuración no hay cargos
+    /// 
+    /// Retorna: i128 - El cargo aplicado
+    fn close_loan(
+        env: &Env,
+        member: &Address,
+        book_id: u32,
+        payer: &Address,
+        charge: impl Fn(&Env, &Loan, &LibraryConfig) -> i128,
+    ) -> i128 {
+        Self::change_active_loans(env, member, false);
+
+        let loan = Self::get_loan(env.clone(), book_id);
+        env.storage().persistent().remove(&DataKey::Loan(book_id));
+
+        let (Some(loan), Some(config)) = (loan, Self::get_config(env.clone())) else {
+            return 0;
+        };
+        let amount = charge(env, &loan, &config);
+        Self::change_held_deposits(env, -loan.deposit);
+
+        let token = token::Client::new(env, &config.token);
+        let contract = env.current_contract_address();
+        if let Some(depositor) = loan.payer.as_ref().filter(|_| loan.deposit > amount) {
+            token.transfer(&contract, depositor, &(loan.deposit - amount));
+        } else if amount > loan.deposit {
+            token.transfer(payer, &contract, &(amount - loan.deposit));
+        }
+
+        amount
+    }
+
+    /// 🔧 FUNCIÓN HELPER: ACTUALIZAR DEPÓSITOS EN GARANTÍA
+    /// Suma (o resta, con un monto negativo) al total de depósitos retenidos
+    fn change_held_deposits(env: &Env, amount: i128) {
+        let held: i128 = env.storage().instance().get(&DEPOSITS_KEY).unwrap_or(0);
+        env.storage().instance().set(&DEPOSITS_KEY, &(held + amount));
+    }
+
+    /// 🔧 FUNCIÓN HELPER: CALCULAR MULTA POR ATRASO
+    /// `late_fee_per_day` por cada día (o fracción) de atraso, sin superar
+    /// nunca el costo de reponer el libro
+    fn late_fee(env: &Env, loan: &Loan, config: &LibraryConfig) -> i128 {
+        let now = env.ledger().timestamp();
+        if now <= loan.due_at {
+            return 0;
+        }
+
+        let days_late = (now - loan.due_at).div_ceil(SECONDS_PER_DAY) as i128;
+        days_late
+            .saturating_mul(config.late_fee_per_day)
+            .min(config.replacement_cost)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: DEVOLVER A NOMBRE DE UN MIEMBRO
+    /// Lógica común de return_book y return_book_for (la autorización ya se verificó)
+    /// `payer` es quien firmó la devolución y paga la multa que el depósito no cubra
+    fn return_as(
+        env: &Env,
+        member: Address,
+        book_id: u32,
+        payer: &Address,
+    ) -> Result<(), LibraryError> {
+        // 📖 OBTENER LIBRO: Verificamos que existe
+        let mut book: Book = env
+            .storage()
+            .instance()
+            .get(&book_id)
+            .ok_or(LibraryError::BookNotFound)?;
+
         // ✅ VERIFICAR QUE ESTÉ PRESTADO: Solo podemos devolver libros prestados
         match book.status {
             BookStatus::Borrowed => {
+                // 👤 VERIFICAR PRESTATARIO: Solo quien lo tiene puede devolverlo
+                // (un libro prestado sin prestatario registrado no lo devuelve nadie)
+                if book.borrower.as_ref() != Some(&member) {
+                    return Err(LibraryError::Unauthorized);
+                }
+
+                // 💰 MULTA: Se descuenta del depósito lo que cueste el atraso
+                Self::close_loan(env, &member, book_id, payer, Self::late_fee);
+                book.borrower = None;
+
                 // 🔄 CAMBIAR ESTADO: Lo marcamos como disponible nuevamente
-                Self::change_book_status(env, book_id, BookStatus::Available)
+                book.status = BookStatus::Available;
+                env.storage().instance().set(&book_id, &book);
+
+                Ok(())
             }
             _ => {
                 // ❌ NO ESTABA PRESTADO: No se puede devolver un libro que no estaba prestado
@@ -201,6 +854,71 @@ impl LibraryContract {
         }
     }
 
+    /// 🔧 FUNCIÓN HELPER: PRESTAR LIBRO A UN MIEMBRO
+    /// Registra el préstamo en el historial del miembro y marca el libro como prestado
+    /// Se usa tanto en préstamos directos como al aprobar una solicitud
+    /// El depósito solo se cobra a `payer`, que debe haber firmado la transacción:
+    /// los préstamos que autoriza otro (bibliotecario, kiosco, dueño) no llevan depósito
+    fn lend_book(
+        env: &Env,
+        member: Address,
+        book_id: u32,
+        payer: Option<&Address>,
+    ) -> Result<(), LibraryError> {
+        // 🎫 MEMBRESÍA: Solo prestamos a miembros con la membresía al día
+        Self::require_active_membership(env, &member)?;
+
+        // 🔢 LÍMITE: Ni el miembro ni sus delegados pueden pasarse del máximo de préstamos
+        Self::require_loan_capacity(env, &member)?;
+
+        // 🔒 RESERVA: Si el libro está reservado, solo quien lo reservó puede llevarlo
+        if let Some(hold) = Self::get_hold(env.clone(), book_id) {
+            if hold.member != member {
+                return Err(LibraryError::BookNotAvailable);
+            }
+        }
+        env.storage().temporary().remove(&DataKey::Hold(book_id));
+
+        // 💰 DEPÓSITO: Con configuración, quien firmó deja un depósito de garantía
+        let mut deposit = 0;
+        let config = Self::get_config(env.clone()).filter(|config| config.deposit > 0);
+        if let (Some(config), Some(payer)) = (config, payer) {
+            token::Client::new(env, &config.token).transfer(
+                payer,
+                &env.current_contract_address(),
+                &config.deposit,
+            );
+            Self::change_held_deposits(env, config.deposit);
+            deposit = config.deposit;
+        }
+
+        // 📝 HISTORIAL: Recordamos que este miembro leyó el libro (permite calificarlo)
+        Self::save_persistent(env, &DataKey::HasBorrowed(member.clone(), book_id), &true);
+
+        // 🔄 CAMBIAR ESTADO: Lo marcamos como prestado a nombre del miembro
+        let mut book: Book = env
+            .storage()
+            .instance()
+            .get(&book_id)
+            .ok_or(LibraryError::BookNotFound)?;
+        book.status = BookStatus::Borrowed;
+        book.borrower = Some(member.clone());
+        env.storage().instance().set(&book_id, &book);
+        Self::change_active_loans(env, &member, true);
+
+        // ⏰ PLAZO: Registramos cuándo debe devolverse
+        let loan = Loan {
+            member,
+            due_at: env.ledger().timestamp() + LOAN_PERIOD,
+            reminded: false,
+            deposit,
+            payer: payer.filter(|_| deposit > 0).cloned(),
+        };
+        Self::save_persistent(env, &DataKey::Loan(book_id), &loan);
+
+        Ok(())
+    }
+
     /// 🔧 FUNCIÓN HELPER: OBTENER PRÓXIMO ID
     /// Función interna que obtiene el próximo ID disponible para nuevos libros
     /// 
@@ -219,9 +937,10 @@ impl LibraryContract {
     /// 
     /// Parámetros:
     /// - env: Entorno de Soroban
+    /// - branch: Some(id) para ver solo los libros que están en esa sede, None para todas
     /// 
     /// Retorna: Vec<Book> - Vector con los libros disponibles
-    pub fn get_available_books(env: Env) -> Vec<Book> {
+    pub fn get_available_books(env: Env, branch: Option<u32>) -> Vec<Book> {
         // 🆕 CREAR VECTOR VACÍO: Creamos un vector en el entorno de Soroban
         let mut available_books = Vec::new(&env);
         
@@ -232,8 +951,11 @@ impl LibraryContract {
         for id in 1..next_id {
             // 📖 INTENTAR OBTENER CADA LIBRO: if let es una forma concisa de manejar Options
             if let Some(book) = Self::get_book(env.clone(), id) {
+                // 🏢 FILTRAR POR SEDE: Si se pidió una sede, ignoramos las demás
+                let in_branch = branch.is_none_or(|id| book.current_branch == id);
+
                 // ✅ FILTRAR SOLO DISPONIBLES: Verificamos el estado
-                if book.status == BookStatus::Available {
+                if book.status == BookStatus::Available && in_branch {
                     // ➕ AGREGAR AL RESULTADO: Añadimos al final del vector
                     available_books.push_back(book);
                 }
@@ -270,34 +992,1848 @@ impl LibraryContract {
     }
 
     /// 🔒 RESERVAR LIBRO
-    /// Permite reservar un libro disponible para uso futuro
+    /// Permite a un miembro reservar un libro disponible durante HOLD_LEDGERS ledgers.
+    /// Mientras dure la reserva solo ese miembro puede tomarlo prestado; al vencer,
+    /// el libro vuelve a estar disponible por sí solo
     /// 
     /// Parámetros:
     /// - env: Entorno de Soroban
+    /// - member: Dirección del miembro que reserva (debe firmar)
     /// - book_id: ID del libro a reservar
     /// 
     /// Retorna: Result<(), LibraryError> - Éxito o error si no está disponible
-    pub fn reserve_book(env: Env, book_id: u32) -> Result<(), LibraryError> {
-        // 📖 OBTENER LIBRO: Verificamos que existe
-        let book: Book = env
-            .storage()
-            .instance()
-            .get(&book_id)
-            .ok_or(LibraryError::BookNotFound)?;
+    pub fn reserve_book(env: Env, member: Address, book_id: u32) -> Result<(), LibraryError> {
+        // 🔐 AUTORIZACIÓN Y MEMBRESÍA: El miembro firma y debe tener la membresía al día
+        member.require_auth();
+        Self::require_active_membership(&env, &member)?;
+
+        // 📖 OBTENER LIBRO: Verificamos que existe (con la reserva vigente aplicada)
+        let book = Self::get_book(env.clone(), book_id).ok_or(LibraryError::BookNotFound)?;
 
         // ✅ VERIFICAR DISPONIBILIDAD: Solo podemos reservar libros disponibles
-        match book.status {
-            BookStatus::Available => {
-                // 🔄 CAMBIAR ESTADO: Lo marcamos como reservado
-                Self::change_book_status(env, book_id, BookStatus::Reserved)
-            }
-            _ => {
-                // ❌ NO DISPONIBLE: No se puede reservar un libro no disponible
-                Err(LibraryError::BookNotAvailable)
-            }
+        if book.status != BookStatus::Available {
+            return Err(LibraryError::BookNotAvailable);
+        }
+
+        // 🪙 LIBRO CON DUEÑO / 📨 LIBRO RESTRINGIDO: Una reserva no puede saltear
+        // la decisión del dueño ni la aprobación de un bibliotecario
+        if book.owner.is_some() || book.restricted {
+            return Err(LibraryError::Unauthorized);
         }
+
+        // ⏳ RESERVA TEMPORAL: El TTL de la entrada coincide con la duración de la reserva
+        let hold = Hold {
+            member,
+            expires_at: env.ledger().sequence() + HOLD_LEDGERS,
+        };
+        let key = DataKey::Hold(book_id);
+        env.storage().temporary().set(&key, &hold);
+        env.storage()
+            .temporary()
+            .extend_ttl(&key, HOLD_LEDGERS, HOLD_LEDGERS);
+
+        Ok(())
     }
-}
 
-// Incluimos el archivo de tests (separado para mejor organización)
-mod test;
+    /// 🔍 OBTENER RESERVA DE UN LIBRO
+    /// Una reserva que ya no está en el storage (o que venció) cuenta como vencida
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - book_id: ID del libro
+    /// 
+    /// Retorna: Option<Hold> - La reserva vigente, o None si no hay
+    pub fn get_hold(env: Env, book_id: u32) -> Option<Hold> {
+        let hold: Hold = env.storage().temporary().get(&DataKey::Hold(book_id))?;
+
+        if env.ledger().sequence() >= hold.expires_at {
+            return None;
+        }
+
+        Some(hold)
+    }
+
+    /// ⭐ CALIFICAR LIBRO
+    /// Permite a un miembro dejar una reseña de un libro que ya tomó prestado
+    /// Cada miembro puede calificar un mismo libro una sola vez
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - member: Dirección del miembro que califica (debe firmar)
+    /// - book_id: ID del libro a calificar
+    /// - stars: Estrellas otorgadas (de 1 a 5)
+    /// - comment: Comentario de la reseña (máximo 280 bytes)
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si la reseña no es válida
+    pub fn rate_book(
+        env: Env,
+        member: Address,
+        book_id: u32,
+        stars: u32,
+        comment: String,
+    ) -> Result<(), LibraryError> {
+        // 🔐 AUTORIZACIÓN: Solo el propio miembro puede dejar su reseña
+        member.require_auth();
+
+        // 📖 VERIFICAR QUE EL LIBRO EXISTE
+        if !env.storage().instance().has(&book_id) {
+            return Err(LibraryError::BookNotFound);
+        }
+
+        // 🔍 VALIDACIÓN: Estrellas dentro del rango y comentario acotado
+        if !(MIN_STARS..=MAX_STARS).contains(&stars) || comment.len() > MAX_COMMENT_LEN {
+            return Err(LibraryError::InvalidRating);
+        }
+
+        // 📝 VERIFICAR HISTORIAL: Solo califican quienes tomaron prestado el libro
+        let has_borrowed: bool =
+            Self::load_persistent(&env, &DataKey::HasBorrowed(member.clone(), book_id))
+                .unwrap_or(false);
+        if !has_borrowed {
+            return Err(LibraryError::NotBorrowedByMember);
+        }
+
+        // 🚫 UNA RESEÑA POR MIEMBRO: Evitamos que un miembro infle el promedio
+        let review_key = DataKey::Review(book_id, member.clone());
+        if env.storage().persistent().has(&review_key) {
+            return Err(LibraryError::AlreadyRated);
+        }
+
+        // 💾 GUARDAR RESEÑA
+        let review = Review {
+            member,
+            stars,
+            comment,
+        };
+        Self::save_persistent(&env, &review_key, &review);
+
+        // 📊 ACTUALIZAR RESUMEN: Recalculamos el promedio con la nueva reseña
+        let mut summary = Self::get_rating_summary(&env, book_id);
+        summary.count += 1;
+        summary.total_stars += stars;
+        summary.average_x100 = summary.total_stars * 100 / summary.count;
+        Self::save_persistent(&env, &DataKey::Rating(book_id), &summary);
+
+        Ok(())
+    }
+
+    /// 🔍 OBTENER RESEÑA
+    /// Busca la reseña que un miembro dejó sobre un libro
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - book_id: ID del libro
+    /// - member: Dirección del miembro que escribió la reseña
+    /// 
+    /// Retorna: Option<Review> - Some(review) si existe, None si no existe
+    pub fn get_review(env: Env, book_id: u32, member: Address) -> Option<Review> {
+        Self::load_persistent(&env, &DataKey::Review(book_id, member))
+    }
+
+    /// 📖 OBTENER LIBRO CON CALIFICACIONES
+    /// Devuelve un libro junto con el resumen agregado de sus reseñas
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - book_id: ID del libro a consultar
+    /// 
+    /// Retorna: Result<BookDetails, LibraryError> - Libro y calificaciones o error
+    pub fn get_book_details(env: Env, book_id: u32) -> Result<BookDetails, LibraryError> {
+        let book = Self::get_book(env.clone(), book_id).ok_or(LibraryError::BookNotFound)?;
+
+        let rating = Self::get_rating_summary(&env, book_id);
+
+        Ok(BookDetails { book, rating })
+    }
+
+    /// 🔒 MARCAR LIBRO COMO RESTRINGIDO
+    /// Permite a un bibliotecario indicar si un libro requiere aprobación para prestarse
+    /// (por ejemplo, ediciones raras que no deben salir sin supervisión)
+    /// Con consejo, solo mediante AdminAction::SetBookRestricted
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - librarian: Dirección del bibliotecario (debe firmar)
+    /// - book_id: ID del libro
+    /// - restricted: true para exigir aprobación, false para préstamo libre
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error
+    pub fn set_book_restricted(
+        env: Env,
+        librarian: Address,
+        book_id: u32,
+        restricted: bool,
+    ) -> Result<(), LibraryError> {
+        Self::require_librarian(&env, &librarian)?;
+        Self::require_no_council(&env)?;
+
+        Self::apply_set_book_restricted(&env, book_id, restricted)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: MARCAR LIBRO COMO RESTRINGIDO
+    /// Aplica el cambio sin verificar permisos (lo hacen set_book_restricted() y el consejo)
+    fn apply_set_book_restricted(env: &Env, book_id: u32, restricted: bool) -> Result<(), LibraryError> {
+        let mut book: Book = env
+            .storage()
+            .instance()
+            .get(&book_id)
+            .ok_or(LibraryError::BookNotFound)?;
+
+        book.restricted = restricted;
+        env.storage().instance().set(&book_id, &book);
+
+        Ok(())
+    }
+
+    /// ✅ APROBAR SOLICITUD DE PRÉSTAMO
+    /// Un bibliotecario aprueba una solicitud pendiente y el libro queda prestado
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - librarian: Dirección del bibliotecario (debe firmar)
+    /// - request_id: ID de la solicitud
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si el libro ya no está disponible
+    pub fn approve_request(env: Env, librarian: Address, request_id: u32) -> Result<(), LibraryError> {
+        Self::require_librarian(&env, &librarian)?;
+
+        let mut request = Self::get_pending_request(&env, request_id)?;
+
+        // 📖 El libro debe seguir disponible para poder prestarlo
+        let book: Book = env
+            .storage()
+            .instance()
+            .get(&request.book_id)
+            .ok_or(LibraryError::BookNotFound)?;
+        if book.status != BookStatus::Available {
+            return Err(LibraryError::BookNotAvailable);
+        }
+
+        // 💰 Firma solo el bibliotecario, así que el préstamo no lleva depósito
+        Self::lend_book(&env, request.member.clone(), request.book_id, None)?;
+
+        // 💾 CERRAR SOLICITUD
+        request.status = RequestStatus::Approved;
+        Self::close_request(&env, &request);
+
+        // 📣 EVENTO: Notificamos la aprobación
+        env.events().publish(
+            (symbol_short!("approved"), request_id),
+            (request.member, request.book_id),
+        );
+
+        Ok(())
+    }
+
+    /// 🚫 RECHAZAR SOLICITUD DE PRÉSTAMO
+    /// Un bibliotecario rechaza una solicitud pendiente; el libro no cambia de estado
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - librarian: Dirección del bibliotecario (debe firmar)
+    /// - request_id: ID de la solicitud
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error
+    pub fn reject_request(env: Env, librarian: Address, request_id: u32) -> Result<(), LibraryError> {
+        Self::require_librarian(&env, &librarian)?;
+
+        let mut request = Self::get_pending_request(&env, request_id)?;
+
+        // 💾 CERRAR SOLICITUD
+        request.status = RequestStatus::Rejected;
+        Self::close_request(&env, &request);
+
+        // 📣 EVENTO: Notificamos el rechazo
+        env.events().publish(
+            (symbol_short!("rejected"), request_id),
+            (request.member, request.book_id),
+        );
+
+        Ok(())
+    }
+
+    /// 🔍 OBTENER SOLICITUD DE PRÉSTAMO
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - request_id: ID de la solicitud
+    /// 
+    /// Retorna: Option<BorrowRequest> - Some(request) si existe, None si no existe
+    pub fn get_request(env: Env, request_id: u32) -> Option<BorrowRequest> {
+        env.storage().instance().get(&DataKey::Request(request_id))
+    }
+
+    /// 📋 OBTENER SOLICITUDES PENDIENTES
+    /// Retorna todas las solicitudes que esperan la decisión de un bibliotecario
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// 
+    /// Retorna: Vec<BorrowRequest> - Solicitudes pendientes en orden de creación
+    pub fn get_pending_requests(env: Env) -> Vec<BorrowRequest> {
+        let mut pending = Vec::new(&env);
+        let next_id: u32 = env.storage().instance().get(&NEXT_REQUEST_KEY).unwrap_or(1);
+
+        for id in 1..next_id {
+            if let Some(request) = Self::get_request(env.clone(), id) {
+                if request.status == RequestStatus::Pending {
+                    pending.push_back(request);
+                }
+            }
+        }
+
+        pending
+    }
+
+    /// 🔧 FUNCIÓN HELPER: CREAR SOLICITUD DE PRÉSTAMO
+    /// Guarda una solicitud pendiente y emite un evento con su ID
+    fn create_borrow_request(env: &Env, member: Address, book_id: u32) -> Result<(), LibraryError> {
+        // 🎫 MEMBRESÍA: Un miembro con la membresía vencida no puede pedir libros
+        Self::require_active_membership(env, &member)?;
+
+        // 🚫 Un miembro no puede tener dos solicitudes pendientes del mismo libro
+        let pending_key = DataKey::PendingRequest(member.clone(), book_id);
+        if env.storage().instance().has(&pending_key) {
+            return Err(LibraryError::RequestAlreadyPending);
+        }
+
+        let request_id: u32 = env.storage().instance().get(&NEXT_REQUEST_KEY).unwrap_or(1);
+        let request = BorrowRequest {
+            id: request_id,
+            book_id,
+            member: member.clone(),
+            status: RequestStatus::Pending,
+        };
+
+        env.storage().instance().set(&DataKey::Request(request_id), &request);
+        env.storage().instance().set(&pending_key, &request_id);
+        env.storage().instance().set(&NEXT_REQUEST_KEY, &(request_id + 1));
+
+        // 📣 EVENTO: El miembro y los bibliotecarios conocen el ID de la solicitud
+        env.events()
+            .publish((symbol_short!("requested"), request_id), (member, book_id));
+
+        Ok(())
+    }
+
+    /// 🔧 FUNCIÓN HELPER: OBTENER SOLICITUD PENDIENTE
+    /// Devuelve la solicitud solo si todavía espera una decisión
+    fn get_pending_request(env: &Env, request_id: u32) -> Result<BorrowRequest, LibraryError> {
+        let request: BorrowRequest = env
+            .storage()
+            .instance()
+            .get(&DataKey::Request(request_id))
+            .ok_or(LibraryError::RequestNotFound)?;
+
+        if request.status != RequestStatus::Pending {
+            return Err(LibraryError::RequestNotPending);
+        }
+
+        Ok(request)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: CERRAR SOLICITUD
+    /// Guarda el estado final de la solicitud y libera al miembro para pedir de nuevo
+    fn close_request(env: &Env, request: &BorrowRequest) {
+        env.storage()
+            .instance()
+            .set(&DataKey::Request(request.id), request);
+        env.storage()
+            .instance()
+            .remove(&DataKey::PendingRequest(request.member.clone(), request.book_id));
+    }
+
+    /// 🔧 FUNCIÓN HELPER: OBTENER ADMINISTRADOR
+    /// Retorna error si la biblioteca no fue inicializada
+    fn get_admin(env: &Env) -> Result<Address, LibraryError> {
+        env.storage()
+            .instance()
+            .get(&ADMIN_KEY)
+            .ok_or(LibraryError::NotInitialized)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: VERIFICAR ADMINISTRADOR
+    /// Exige la firma del administrador para una operación sensible.
+    /// Una vez que existe un consejo, el administrador ya no puede actuar solo
+    /// y la operación debe pasar por propose / approve_proposal
+    fn require_admin(env: &Env) -> Result<(), LibraryError> {
+        let admin = Self::get_admin(env)?;
+        Self::require_no_council(env)?;
+
+        admin.require_auth();
+        Ok(())
+    }
+
+    /// 🔧 FUNCIÓN HELPER: VERIFICAR QUE NO HAY CONSEJO
+    /// Con consejo, las operaciones sensibles solo se ejecutan mediante propuestas
+    fn require_no_council(env: &Env) -> Result<(), LibraryError> {
+        if env.storage().instance().has(&COUNCIL_KEY) {
+            return Err(LibraryError::CouncilRequired);
+        }
+
+        Ok(())
+    }
+
+    /// 🔧 FUNCIÓN HELPER: VERIFICAR BIBLIOTECARIO
+    /// Exige la firma de la dirección y que sea el administrador o un bibliotecario
+    fn require_librarian(env: &Env, librarian: &Address) -> Result<(), LibraryError> {
+        librarian.require_auth();
+
+        let admin = Self::get_admin(env)?;
+        let is_librarian = env
+            .storage()
+            .instance()
+            .has(&DataKey::Librarian(librarian.clone()));
+
+        if *librarian != admin && !is_librarian {
+            return Err(LibraryError::Unauthorized);
+        }
+
+        Ok(())
+    }
+
+    /// 🏢 AGREGAR SEDE
+    /// Permite al administrador de la biblioteca abrir una nueva sede
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - name: Nombre de la sede
+    /// - branch_admin: Administrador de la nueva sede
+    /// 
+    /// Retorna: Result<u32, LibraryError> - El ID de la nueva sede o un error
+    pub fn add_branch(env: Env, name: String, branch_admin: Address) -> Result<u32, LibraryError> {
+        Self::require_admin(&env)?;
+
+        Self::apply_add_branch(&env, name, branch_admin)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: AGREGAR SEDE
+    /// Aplica el cambio sin verificar permisos (lo hacen add_branch() y el consejo)
+    fn apply_add_branch(env: &Env, name: String, branch_admin: Address) -> Result<u32, LibraryError> {
+        if name.is_empty() {
+            return Err(LibraryError::InvalidBookData);
+        }
+
+        let branch_id: u32 = env
+            .storage()
+            .instance()
+            .get(&NEXT_BRANCH_KEY)
+            .unwrap_or(MAIN_BRANCH_ID + 1);
+        let branch = Branch {
+            id: branch_id,
+            name,
+            admin: branch_admin,
+        };
+
+        env.storage().instance().set(&DataKey::Branch(branch_id), &branch);
+        env.storage().instance().set(&NEXT_BRANCH_KEY, &(branch_id + 1));
+
+        Ok(branch_id)
+    }
+
+    /// 👩‍💼 CAMBIAR ADMINISTRADOR DE SEDE
+    /// Permite al administrador de la biblioteca reasignar la sede a otra persona
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - branch_id: ID de la sede
+    /// - branch_admin: Nuevo administrador de la sede
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si la sede no existe
+    pub fn set_branch_admin(env: Env, branch_id: u32, branch_admin: Address) -> Result<(), LibraryError> {
+        Self::require_admin(&env)?;
+
+        Self::apply_set_branch_admin(&env, branch_id, branch_admin)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: CAMBIAR ADMINISTRADOR DE SEDE
+    /// Aplica el cambio sin verificar permisos (lo hacen set_branch_admin() y el consejo)
+    fn apply_set_branch_admin(env: &Env, branch_id: u32, branch_admin: Address) -> Result<(), LibraryError> {
+        let mut branch = Self::get_branch(env.clone(), branch_id).ok_or(LibraryError::BranchNotFound)?;
+        branch.admin = branch_admin;
+        env.storage().instance().set(&DataKey::Branch(branch_id), &branch);
+
+        Ok(())
+    }
+
+    /// 🔍 OBTENER SEDE POR ID
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - branch_id: ID de la sede
+    /// 
+    /// Retorna: Option<Branch> - Some(branch) si existe, None si no existe
+    pub fn get_branch(env: Env, branch_id: u32) -> Option<Branch> {
+        env.storage().instance().get(&DataKey::Branch(branch_id))
+    }
+
+    /// 🚚 ENVIAR LIBRO A OTRA SEDE
+    /// Inicia el traslado de un ejemplar disponible; queda "InTransit" hasta que
+    /// la sede de destino confirme la recepción con receive_book
+    /// Debe firmar el administrador de la sede de origen
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - book_id: ID del libro a trasladar
+    /// - from: Sede donde está el libro ahora
+    /// - to: Sede de destino
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error
+    pub fn transfer_book(env: Env, book_id: u32, from: u32, to: u32) -> Result<(), LibraryError> {
+        let from_branch = Self::get_branch(env.clone(), from).ok_or(LibraryError::BranchNotFound)?;
+        if !env.storage().instance().has(&DataKey::Branch(to)) {
+            return Err(LibraryError::BranchNotFound);
+        }
+        from_branch.admin.require_auth();
+
+        let mut book: Book = env
+            .storage()
+            .instance()
+            .get(&book_id)
+            .ok_or(LibraryError::BookNotFound)?;
+
+        // 🔍 VALIDACIÓN: El libro debe estar en la sede de origen y el destino ser otra sede
+        if from == to || book.current_branch != from {
+            return Err(LibraryError::InvalidTransfer);
+        }
+        // Un libro reservado espera a su lector en la sede donde está
+        if book.status != BookStatus::Available || Self::get_hold(env.clone(), book_id).is_some() {
+            return Err(LibraryError::BookNotAvailable);
+        }
+
+        book.status = BookStatus::InTransit;
+        book.in_transit_to = Some(to);
+        env.storage().instance().set(&book_id, &book);
+
+        // 📣 EVENTO: La sede de destino sabe que el libro está en camino
+        env.events()
+            .publish((symbol_short!("transfer"), book_id), (from, to));
+
+        Ok(())
+    }
+
+    /// 📦 RECIBIR LIBRO EN TRÁNSITO
+    /// La sede de destino confirma la llegada y el libro vuelve a estar disponible allí
+    /// Debe firmar el administrador de la sede de destino
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - book_id: ID del libro recibido
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si el libro no está en tránsito
+    pub fn receive_book(env: Env, book_id: u32) -> Result<(), LibraryError> {
+        let mut book: Book = env
+            .storage()
+            .instance()
+            .get(&book_id)
+            .ok_or(LibraryError::BookNotFound)?;
+
+        let to = match (book.status.clone(), book.in_transit_to) {
+            (BookStatus::InTransit, Some(to)) => to,
+            _ => return Err(LibraryError::InvalidTransfer),
+        };
+
+        let to_branch = Self::get_branch(env.clone(), to).ok_or(LibraryError::BranchNotFound)?;
+        to_branch.admin.require_auth();
+
+        let from = book.current_branch;
+        book.status = BookStatus::Available;
+        book.current_branch = to;
+        book.in_transit_to = None;
+        env.storage().instance().set(&book_id, &book);
+
+        // 📣 EVENTO: El traslado terminó
+        env.events()
+            .publish((symbol_short!("received"), book_id), (from, to));
+
+        Ok(())
+    }
+
+    /// 🤝 PEDIR PRESTADO A OTRA BIBLIOTECA
+    /// Préstamo interbibliotecario: esta biblioteca toma prestado un libro de otra
+    /// instancia de LibraryContract mediante una llamada entre contratos.
+    /// En la biblioteca que presta, el libro queda a nombre de la dirección de este contrato
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - librarian: Bibliotecario que gestiona el préstamo (debe firmar)
+    /// - partner: Dirección del contrato de la biblioteca asociada
+    /// - book_id: ID del libro en la biblioteca asociada
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o el error devuelto por la biblioteca asociada
+    pub fn borrow_from_partner(
+        env: Env,
+        librarian: Address,
+        partner: Address,
+        book_id: u32,
+    ) -> Result<(), LibraryError> {
+        Self::require_librarian(&env, &librarian)?;
+
+        // 📞 LLAMADA ENTRE CONTRATOS: Usamos el cliente generado por #[contractimpl]
+        // Como este contrato hace la llamada directamente, su require_auth se cumple solo
+        let partner_client = LibraryContractClient::new(&env, &partner);
+        let this_library = env.current_contract_address();
+
+        // 💰 DEPÓSITO: Si la otra biblioteca lo cobra, esta lo paga de su tesorería.
+        // La transferencia no la invoca este contrato, así que hay que autorizarla
+        if let Some(config) = partner_client.get_config().filter(|config| config.deposit > 0) {
+            Self::authorize_partner_payment(&env, &config.token, &partner, config.deposit);
+        }
+        Self::map_partner_result(partner_client.try_borrow_book(&this_library, &book_id))?;
+
+        // ✅ VERIFICAR: Un libro restringido solo genera una solicitud, no un préstamo
+        let book = partner_client
+            .get_book(&book_id)
+            .ok_or(LibraryError::BookNotFound)?;
+        if book.borrower != Some(this_library) {
+            return Err(LibraryError::BookNotAvailable);
+        }
+
+        // 💾 REGISTRAR: Guardamos una copia del libro prestado por la otra biblioteca
+        env.storage()
+            .instance()
+            .set(&DataKey::PartnerLoan(partner, book_id), &book);
+
+        Ok(())
+    }
+
+    /// 🤝 DEVOLVER A OTRA BIBLIOTECA
+    /// Devuelve un libro obtenido con borrow_from_partner, también mediante
+    /// una llamada entre contratos
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - librarian: Bibliotecario que gestiona la devolución (debe firmar)
+    /// - partner: Dirección del contrato de la biblioteca asociada
+    /// - book_id: ID del libro en la biblioteca asociada
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si no había préstamo
+    pub fn return_to_partner(
+        env: Env,
+        librarian: Address,
+        partner: Address,
+        book_id: u32,
+    ) -> Result<(), LibraryError> {
+        Self::require_librarian(&env, &librarian)?;
+
+        let loan_key = DataKey::PartnerLoan(partner.clone(), book_id);
+        if !env.storage().instance().has(&loan_key) {
+            return Err(LibraryError::PartnerLoanNotFound);
+        }
+
+        let partner_client = LibraryContractClient::new(&env, &partner);
+        let this_library = env.current_contract_address();
+
+        // 💰 MULTA: La parte que el depósito no cubre sale de la tesorería de esta biblioteca
+        let loan = partner_client.get_loan(&book_id);
+        if let (Some(loan), Some(config)) = (loan, partner_client.get_config()) {
+            let owed = Self::late_fee(&env, &loan, &config) - loan.deposit;
+            if owed > 0 {
+                Self::authorize_partner_payment(&env, &config.token, &partner, owed);
+            }
+        }
+        Self::map_partner_result(partner_client.try_return_book(&this_library, &book_id))?;
+
+        env.storage().instance().remove(&loan_key);
+
+        Ok(())
+    }
+
+    /// 🔍 OBTENER PRÉSTAMO INTERBIBLIOTECARIO
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - partner: Dirección del contrato de la biblioteca asociada
+    /// - book_id: ID del libro en la biblioteca asociada
+    /// 
+    /// Retorna: Option<Book> - El libro prestado por la otra biblioteca, si hay préstamo vigente
+    pub fn get_partner_loan(env: Env, partner: Address, book_id: u32) -> Option<Book> {
+        env.storage()
+            .instance()
+            .get(&DataKey::PartnerLoan(partner, book_id))
+    }
+
+    /// 🔧 FUNCIÓN HELPER: AUTORIZAR UN PAGO A OTRA BIBLIOTECA
+    /// Autoriza la transferencia `amount` de este contrato a `partner` que la otra
+    /// biblioteca hará durante la llamada (un require_auth más allá del invocador directo)
+    fn authorize_partner_payment(env: &Env, token: &Address, partner: &Address, amount: i128) {
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token.clone(),
+                    fn_name: Symbol::new(env, "transfer"),
+                    args: (env.current_contract_address(), partner.clone(), amount).into_val(env),
+                },
+                sub_invocations: vec![env],
+            }),
+        ]);
+    }
+
+    /// 🔧 FUNCIÓN HELPER: TRADUCIR RESPUESTA DE OTRA BIBLIOTECA
+    /// Propaga los LibraryError de la biblioteca asociada; cualquier otra falla
+    /// (contrato inexistente, tipos incompatibles) se reporta como Unauthorized
+    fn map_partner_result<T, C, I>(
+        result: Result<Result<T, C>, Result<LibraryError, I>>,
+    ) -> Result<(), LibraryError> {
+        match result {
+            Ok(Ok(_)) => Ok(()),
+            Err(Ok(error)) => Err(error),
+            _ => Err(LibraryError::Unauthorized),
+        }
+    }
+
+    /// 🪙 CREAR LIBRO CON DUEÑO (MODO NFT)
+    /// Registra un ejemplar que pertenece a un miembro, como un token no fungible.
+    /// El dueño puede transferirlo, aprobar a otra dirección y prestarlo.
+    /// Con consejo, el catálogo solo cambia mediante propuestas
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - owner: Dueño del ejemplar (debe firmar)
+    /// - title: Título del libro
+    /// - author: Autor del libro
+    /// 
+    /// Retorna: Result<u32, LibraryError> - El ID del nuevo libro o un error
+    pub fn mint_book(
+        env: Env,
+        owner: Address,
+        title: String,
+        author: String,
+    ) -> Result<u32, LibraryError> {
+        owner.require_auth();
+        Self::require_no_council(&env)?;
+
+        let book_id = Self::create_book(&env, title, author, MAIN_BRANCH_ID)?;
+
+        let mut book: Book = env.storage().instance().get(&book_id).unwrap();
+        book.owner = Some(owner.clone());
+        env.storage().instance().set(&book_id, &book);
+        Self::change_balance(&env, &owner, true);
+
+        // 📣 EVENTO: Convención NFT ("mint", dueño) → ID del token
+        env.events().publish((symbol_short!("mint"), owner), book_id);
+
+        Ok(book_id)
+    }
+
+    /// 🪙 OBTENER DUEÑO
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - book_id: ID del libro
+    /// 
+    /// Retorna: Result<Address, LibraryError> - El dueño o error si el libro no tiene dueño
+    pub fn owner_of(env: Env, book_id: u32) -> Result<Address, LibraryError> {
+        let book: Book = env
+            .storage()
+            .instance()
+            .get(&book_id)
+            .ok_or(LibraryError::BookNotFound)?;
+
+        book.owner.ok_or(LibraryError::BookNotOwned)
+    }
+
+    /// 🪙 CANTIDAD DE LIBROS DE UN DUEÑO
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - owner: Dirección a consultar
+    /// 
+    /// Retorna: u32 - Cantidad de libros con dueño que tiene la dirección
+    pub fn balance_of(env: Env, owner: Address) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::Balance(owner))
+            .unwrap_or(0)
+    }
+
+    /// 🪙 APROBAR TRANSFERENCIA
+    /// El dueño autoriza a otra dirección a transferir el libro una vez
+    /// (la aprobación se borra con cada transferencia)
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - owner: Dueño del libro (debe firmar)
+    /// - approved: Dirección autorizada
+    /// - book_id: ID del libro
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si no es el dueño
+    pub fn approve(env: Env, owner: Address, approved: Address, book_id: u32) -> Result<(), LibraryError> {
+        owner.require_auth();
+
+        if Self::owner_of(env.clone(), book_id)? != owner {
+            return Err(LibraryError::Unauthorized);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Approved(book_id), &approved);
+
+        // 📣 EVENTO: Convención NFT ("approve", dueño, aprobado) → ID del token
+        env.events()
+            .publish((symbol_short!("approve"), owner, approved), book_id);
+
+        Ok(())
+    }
+
+    /// 🪙 OBTENER DIRECCIÓN APROBADA
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - book_id: ID del libro
+    /// 
+    /// Retorna: Option<Address> - La dirección aprobada, si existe
+    pub fn get_approved(env: Env, book_id: u32) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Approved(book_id))
+    }
+
+    /// 🪙 TRANSFERIR LIBRO
+    /// El dueño entrega la propiedad del libro a otra dirección.
+    /// No se puede transferir mientras el libro está prestado
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - from: Dueño actual (debe firmar)
+    /// - to: Nuevo dueño
+    /// - book_id: ID del libro
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error
+    pub fn transfer(env: Env, from: Address, to: Address, book_id: u32) -> Result<(), LibraryError> {
+        from.require_auth();
+
+        Self::move_ownership(&env, from, to, book_id)
+    }
+
+    /// 🪙 TRANSFERIR LIBRO COMO APROBADO
+    /// La dirección aprobada por el dueño transfiere el libro en su nombre
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - spender: Dirección aprobada (debe firmar)
+    /// - from: Dueño actual
+    /// - to: Nuevo dueño
+    /// - book_id: ID del libro
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si no está aprobada
+    pub fn transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        book_id: u32,
+    ) -> Result<(), LibraryError> {
+        spender.require_auth();
+
+        if Self::get_approved(env.clone(), book_id) != Some(spender) {
+            return Err(LibraryError::Unauthorized);
+        }
+
+        Self::move_ownership(&env, from, to, book_id)
+    }
+
+    /// 🪙 PRESTAR LIBRO CON DUEÑO
+    /// El dueño presta su ejemplar a otro miembro; el préstamo funciona igual que
+    /// en un libro de la biblioteca y se devuelve con return_book
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - owner: Dueño del libro (debe firmar)
+    /// - borrower: Miembro que recibe el libro
+    /// - book_id: ID del libro
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si no está disponible
+    pub fn lend_owned_book(
+        env: Env,
+        owner: Address,
+        borrower: Address,
+        book_id: u32,
+    ) -> Result<(), LibraryError> {
+        owner.require_auth();
+
+        if Self::owner_of(env.clone(), book_id)? != owner {
+            return Err(LibraryError::Unauthorized);
+        }
+
+        let book: Book = env.storage().instance().get(&book_id).unwrap();
+        if book.status != BookStatus::Available {
+            return Err(LibraryError::BookNotAvailable);
+        }
+
+        // 💰 El libro no es de la biblioteca y el prestatario no firma: sin depósito
+        Self::lend_book(&env, borrower, book_id, None)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: MOVER PROPIEDAD
+    /// Valida al dueño, cambia la propiedad, borra la aprobación y actualiza balances
+    fn move_ownership(env: &Env, from: Address, to: Address, book_id: u32) -> Result<(), LibraryError> {
+        let mut book: Book = env
+            .storage()
+            .instance()
+            .get(&book_id)
+            .ok_or(LibraryError::BookNotFound)?;
+
+        let owner = book.owner.clone().ok_or(LibraryError::BookNotOwned)?;
+        if owner != from {
+            return Err(LibraryError::Unauthorized);
+        }
+
+        // 📚 Un libro prestado no cambia de dueño hasta que se devuelva
+        if book.status == BookStatus::Borrowed {
+            return Err(LibraryError::BookOnLoan);
+        }
+
+        book.owner = Some(to.clone());
+        env.storage().instance().set(&book_id, &book);
+        env.storage().instance().remove(&DataKey::Approved(book_id));
+        Self::change_balance(env, &from, false);
+        Self::change_balance(env, &to, true);
+
+        // 📣 EVENTO: Convención NFT ("transfer", desde, hacia) → ID del token
+        env.events()
+            .publish((symbol_short!("transfer"), from, to), book_id);
+
+        Ok(())
+    }
+
+    /// 🔧 FUNCIÓN HELPER: ACTUALIZAR BALANCE
+    /// Suma o resta un libro a la cantidad de libros con dueño de una dirección
+    fn change_balance(env: &Env, owner: &Address, increase: bool) {
+        let key = DataKey::Balance(owner.clone());
+        let balance: u32 = env.storage().instance().get(&key).unwrap_or(0);
+        let balance = if increase { balance + 1 } else { balance - 1 };
+        env.storage().instance().set(&key, &balance);
+    }
+
+    /// ⚙️ CONFIGURAR TARIFAS
+    /// El administrador guarda la tabla de tarifas (multas, depósitos, reposición
+    /// y membresías). Desde ese momento prestar y reservar exige una membresía vigente.
+    /// Cada cambio emite un evento con la configuración anterior y la nueva.
+    /// El token no puede cambiar mientras haya depósitos retenidos en el token actual
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - config: Nueva configuración
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si algún valor está fuera de rango
+    /// o si cambia el token con depósitos retenidos
+    pub fn set_config(env: Env, config: LibraryConfig) -> Result<(), LibraryError> {
+        Self::require_admin(&env)?;
+
+        Self::apply_set_config(&env, config)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: CONFIGURAR TARIFAS
+    /// Aplica el cambio sin verificar permisos (lo hacen set_config() y el consejo)
+    fn apply_set_config(env: &Env, config: LibraryConfig) -> Result<(), LibraryError> {
+        // 🔍 VALIDACIÓN: Montos entre 0 y MAX_FEE, multas y depósitos no mayores
+        // que el costo de reposición, un período de membresía razonable y un
+        // límite de préstamos entre 1 y MAX_LOANS_LIMIT
+        let amounts = [
+            config.late_fee_per_day,
+            config.deposit,
+            config.replacement_cost,
+            config.membership_price,
+        ];
+        if amounts.iter().any(|amount| !(0..=MAX_FEE).contains(amount))
+            || config.late_fee_per_day > config.replacement_cost
+            || config.deposit > config.replacement_cost
+            || !(MIN_MEMBERSHIP_PERIOD..=MAX_MEMBERSHIP_PERIOD).contains(&config.membership_period)
+            || !(1..=MAX_LOANS_LIMIT).contains(&config.max_loans)
+        {
+            return Err(LibraryError::InvalidConfig);
+        }
+
+        // 💰 DEPÓSITOS: Se reintegran en el token en que se cobraron, así que no se
+        // puede cambiar de token hasta que se liquiden todos
+        let previous = Self::get_config(env.clone());
+        let held: i128 = env.storage().instance().get(&DEPOSITS_KEY).unwrap_or(0);
+        if held > 0 && previous.as_ref().is_some_and(|previous| previous.token != config.token) {
+            return Err(LibraryError::DepositsLocked);
+        }
+
+        env.storage().instance().set(&CONFIG_KEY, &config);
+
+        // 📣 EVENTO: Los miembros pueden ver cuándo y cuánto cambian los precios
+        env.events()
+            .publish((symbol_short!("config"),), (previous, config));
+
+        Ok(())
+    }
+
+    /// 🔍 OBTENER CONFIGURACIÓN
+    /// Cualquiera puede consultar la tabla de tarifas vigente
+    /// 
+    /// Retorna: Option<LibraryConfig> - La configuración, o None si la biblioteca es gratuita
+    pub fn get_config(env: Env) -> Option<LibraryConfig> {
+        env.storage().instance().get(&CONFIG_KEY)
+    }
+
+    /// 🎫 SUSCRIBIRSE
+    /// Cobra `periods` períodos de membresía según la configuración y extiende la membresía.
+    /// Si la membresía sigue vigente se extiende desde su vencimiento actual;
+    /// si venció (o no existía) se cuenta desde ahora
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - member: Miembro que paga (debe firmar)
+    /// - periods: Cantidad de períodos a pagar (mayor a cero)
+    /// 
+    /// Retorna: Result<Membership, LibraryError> - La membresía actualizada o error
+    pub fn subscribe(env: Env, member: Address, periods: u32) -> Result<Membership, LibraryError> {
+        member.require_auth();
+
+        Self::extend_membership(&env, member, periods)
+    }
+
+    /// 🔁 RENOVAR MEMBRESÍA
+    /// Igual que subscribe, pero solo para quien ya tuvo una membresía:
+    /// el tiempo pagado se suma al vencimiento actual
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - member: Miembro que renueva (debe firmar)
+    /// - periods: Cantidad de períodos a pagar (mayor a cero)
+    /// 
+    /// Retorna: Result<Membership, LibraryError> - La membresía actualizada o error
+    pub fn renew_membership(env: Env, member: Address, periods: u32) -> Result<Membership, LibraryError> {
+        member.require_auth();
+
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::Membership(member.clone()))
+        {
+            return Err(LibraryError::MembershipExpired);
+        }
+
+        Self::extend_membership(&env, member, periods)
+    }
+
+    /// 🔍 OBTENER MEMBRESÍA
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - member: Dirección del miembro
+    /// 
+    /// Retorna: Option<Membership> - La membresía (vigente o vencida) si alguna vez existió
+    pub fn get_membership(env: Env, member: Address) -> Option<Membership> {
+        Self::load_persistent(&env, &DataKey::Membership(member))
+    }
+
+    /// 🔧 FUNCIÓN HELPER: EXTENDER MEMBRESÍA
+    /// Cobra los períodos en el token configurado y mueve el vencimiento
+    fn extend_membership(env: &Env, member: Address, periods: u32) -> Result<Membership, LibraryError> {
+        let config = Self::get_config(env.clone()).ok_or(LibraryError::MembershipNotConfigured)?;
+
+        if periods == 0 {
+            return Err(LibraryError::InvalidPeriod);
+        }
+
+        // 💰 COBRO: El miembro paga al contrato de la biblioteca
+        let amount = config
+            .membership_price
+            .checked_mul(periods as i128)
+            .ok_or(LibraryError::InvalidPeriod)?;
+        let extension = config
+            .membership_period
+            .checked_mul(periods as u64)
+            .ok_or(LibraryError::InvalidPeriod)?;
+        if amount > 0 {
+            token::Client::new(env, &config.token).transfer(
+                &member,
+                &env.current_contract_address(),
+                &amount,
+            );
+        }
+
+        // 📅 VENCIMIENTO: Extendemos desde el vencimiento actual o desde ahora
+        let now = env.ledger().timestamp();
+        let key = DataKey::Membership(member.clone());
+        let current_expiry = Self::load_persistent::<Membership>(env, &key)
+            .map_or(0, |membership| membership.expires_at);
+        let expires_at = current_expiry
+            .max(now)
+            .checked_add(extension)
+            .ok_or(LibraryError::InvalidPeriod)?;
+
+        let membership = Membership { member, expires_at };
+        Self::save_persistent(env, &key, &membership);
+
+        Ok(membership)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: VERIFICAR MEMBRESÍA
+    /// Sin configuración la biblioteca es gratuita; con configuración, la membresía
+    /// debe existir y no haber vencido
+    fn require_active_membership(env: &Env, member: &Address) -> Result<(), LibraryError> {
+        if !env.storage().instance().has(&CONFIG_KEY) {
+            return Ok(());
+        }
+
+        let expires_at = Self::get_membership(env.clone(), member.clone())
+            .map_or(0, |membership| membership.expires_at);
+        if env.ledger().timestamp() >= expires_at {
+            return Err(LibraryError::MembershipExpired);
+        }
+
+        Ok(())
+    }
+
+    /// 💰 RETIRAR FONDOS
+    /// El administrador retira fondos de la tesorería (pagos de membresías)
+    /// en el token de la configuración
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - to: Dirección que recibe los fondos
+    /// - amount: Monto a retirar (mayor a cero)
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error
+    pub fn withdraw(env: Env, to: Address, amount: i128) -> Result<(), LibraryError> {
+        Self::require_admin(&env)?;
+
+        Self::apply_withdraw(&env, to, amount)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: RETIRAR FONDOS
+    /// Aplica el cambio sin verificar permisos (lo hacen withdraw() y el consejo)
+    fn apply_withdraw(env: &Env, to: Address, amount: i128) -> Result<(), LibraryError> {
+        let config = Self::get_config(env.clone()).ok_or(LibraryError::MembershipNotConfigured)?;
+        if amount <= 0 {
+            return Err(LibraryError::InvalidConfig);
+        }
+
+        // 🔒 DEPÓSITOS: Los depósitos de garantía pertenecen a los miembros hasta que devuelven
+        let token = token::Client::new(env, &config.token);
+        let held: i128 = env.storage().instance().get(&DEPOSITS_KEY).unwrap_or(0);
+        if token.balance(&env.current_contract_address()) - amount < held {
+            return Err(LibraryError::DepositsLocked);
+        }
+
+        token.transfer(&env.current_contract_address(), &to, &amount);
+
+        Ok(())
+    }
+
+    /// 🎁 PROPONER DONACIÓN
+    /// Un miembro ofrece donar un libro; queda pendiente hasta que un bibliotecario decida
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - member: Miembro que dona (debe firmar)
+    /// - title: Título del libro ofrecido
+    /// - author: Autor del libro ofrecido
+    /// 
+    /// Retorna: Result<u32, LibraryError> - El ID de la donación o un error
+    pub fn propose_donation(
+        env: Env,
+        member: Address,
+        title: String,
+        author: String,
+    ) -> Result<u32, LibraryError> {
+        member.require_auth();
+
+        if title.is_empty() || author.is_empty() {
+            return Err(LibraryError::InvalidBookData);
+        }
+
+        let donation_id: u32 = env.storage().instance().get(&NEXT_DONATION_KEY).unwrap_or(1);
+        let donation = Donation {
+            id: donation_id,
+            donor: member.clone(),
+            title,
+            author,
+            status: DonationStatus::Pending,
+            book_id: None,
+        };
+        Self::save_persistent(&env, &DataKey::Donation(donation_id), &donation);
+        env.storage()
+            .instance()
+            .set(&NEXT_DONATION_KEY, &(donation_id + 1));
+
+        // 📝 Guardamos el ID en la lista de donaciones del miembro
+        let donor_key = DataKey::DonorDonations(member.clone());
+        let mut donation_ids: Vec<u32> =
+            Self::load_persistent(&env, &donor_key).unwrap_or(Vec::new(&env));
+        donation_ids.push_back(donation_id);
+        Self::save_persistent(&env, &donor_key, &donation_ids);
+
+        // 📣 EVENTO: Los bibliotecarios se enteran de la nueva donación
+        env.events()
+            .publish((symbol_short!("donation"), donation_id), member);
+
+        Ok(donation_id)
+    }
+
+    /// ✅ ACEPTAR DONACIÓN
+    /// Un bibliotecario acepta la donación: se crea un libro en la sede principal
+    /// que registra quién lo donó (con consejo, solo mediante AdminAction::AddBook)
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - librarian: Bibliotecario que acepta (debe firmar)
+    /// - donation_id: ID de la donación
+    /// 
+    /// Retorna: Result<u32, LibraryError> - El ID del nuevo libro o un error
+    pub fn accept_donation(env: Env, librarian: Address, donation_id: u32) -> Result<u32, LibraryError> {
+        Self::require_librarian(&env, &librarian)?;
+        Self::require_no_council(&env)?;
+
+        let mut donation = Self::get_pending_donation(&env, donation_id)?;
+
+        // 📖 CREAR LIBRO: Igual que add_book, pero recordando al donante
+        let book_id = Self::create_book(
+            &env,
+            donation.title.clone(),
+            donation.author.clone(),
+            MAIN_BRANCH_ID,
+        )?;
+        let mut book: Book = env.storage().instance().get(&book_id).unwrap();
+        book.donor = Some(donation.donor.clone());
+        env.storage().instance().set(&book_id, &book);
+
+        donation.status = DonationStatus::Accepted;
+        donation.book_id = Some(book_id);
+        Self::save_persistent(&env, &DataKey::Donation(donation_id), &donation);
+
+        Ok(book_id)
+    }
+
+    /// 🚫 RECHAZAR DONACIÓN
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - librarian: Bibliotecario que rechaza (debe firmar)
+    /// - donation_id: ID de la donación
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error
+    pub fn reject_donation(env: Env, librarian: Address, donation_id: u32) -> Result<(), LibraryError> {
+        Self::require_librarian(&env, &librarian)?;
+
+        let mut donation = Self::get_pending_donation(&env, donation_id)?;
+        donation.status = DonationStatus::Rejected;
+        Self::save_persistent(&env, &DataKey::Donation(donation_id), &donation);
+
+        Ok(())
+    }
+
+    /// 🔍 OBTENER DONACIÓN
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - donation_id: ID de la donación
+    /// 
+    /// Retorna: Option<Donation> - Some(donation) si existe, None si no existe
+    pub fn get_donation(env: Env, donation_id: u32) -> Option<Donation> {
+        Self::load_persistent(&env, &DataKey::Donation(donation_id))
+    }
+
+    /// 📋 OBTENER DONACIONES DE UN MIEMBRO
+    /// Lista las donaciones que propuso un miembro con su estado actual
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - donor: Dirección del miembro donante
+    /// 
+    /// Retorna: Vec<Donation> - Donaciones en orden de propuesta
+    pub fn get_donations(env: Env, donor: Address) -> Vec<Donation> {
+        let mut donations = Vec::new(&env);
+        let donation_ids: Vec<u32> =
+            Self::load_persistent(&env, &DataKey::DonorDonations(donor)).unwrap_or(Vec::new(&env));
+
+        for donation_id in donation_ids.iter() {
+            if let Some(donation) = Self::get_donation(env.clone(), donation_id) {
+                donations.push_back(donation);
+            }
+        }
+
+        donations
+    }
+
+    /// 🔧 FUNCIÓN HELPER: OBTENER DONACIÓN PENDIENTE
+    /// Devuelve la donación solo si todavía espera una decisión
+    fn get_pending_donation(env: &Env, donation_id: u32) -> Result<Donation, LibraryError> {
+        let donation: Donation = Self::load_persistent(env, &DataKey::Donation(donation_id))
+            .ok_or(LibraryError::DonationNotFound)?;
+
+        if donation.status != DonationStatus::Pending {
+            return Err(LibraryError::DonationNotPending);
+        }
+
+        Ok(donation)
+    }
+
+    /// 🏛️ CREAR CONSEJO DE ADMINISTRACIÓN
+    /// El administrador crea el consejo M-de-N una única vez. Desde ese momento
+    /// las operaciones sensibles (tarifas, bibliotecarios, sedes, catálogo, tesorería,
+    /// kioscos y el propio consejo) solo se ejecutan mediante propuestas aprobadas
+    /// por el consejo
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - members: Miembros del consejo (sin repetidos, máximo 10)
+    /// - threshold: Aprobaciones necesarias (entre 1 y la cantidad de miembros)
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error
+    pub fn set_council(env: Env, members: Vec<Address>, threshold: u32) -> Result<(), LibraryError> {
+        Self::require_admin(&env)?;
+
+        Self::apply_set_council(&env, members, threshold)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: CAMBIAR CONSEJO
+    /// Valida y guarda los miembros y el umbral del consejo
+    fn apply_set_council(env: &Env, members: Vec<Address>, threshold: u32) -> Result<(), LibraryError> {
+        if members.is_empty() || members.len() > MAX_COUNCIL_SIZE {
+            return Err(LibraryError::InvalidCouncil);
+        }
+        if threshold == 0 || threshold > members.len() {
+            return Err(LibraryError::InvalidCouncil);
+        }
+
+        // 🚫 Un miembro repetido contaría dos veces hacia el umbral
+        for (i, member) in members.iter().enumerate() {
+            if members.first_index_of(&member) != Some(i as u32) {
+                return Err(LibraryError::InvalidCouncil);
+            }
+        }
+
+        let council = Council { members, threshold };
+        env.storage().instance().set(&COUNCIL_KEY, &council);
+
+        Ok(())
+    }
+
+    /// 🔍 OBTENER CONSEJO
+    /// 
+    /// Retorna: Option<Council> - El consejo, o None si la biblioteca tiene un solo administrador
+    pub fn get_council(env: Env) -> Option<Council> {
+        env.storage().instance().get(&COUNCIL_KEY)
+    }
+
+    /// 📝 PROPONER OPERACIÓN
+    /// Un miembro del consejo propone una operación sensible; su firma cuenta como
+    /// la primera aprobación. Si el umbral es 1 se ejecuta de inmediato
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - member: Miembro del consejo que propone (debe firmar)
+    /// - action: Operación propuesta
+    /// 
+    /// Retorna: Result<u32, LibraryError> - El ID de la propuesta o un error
+    pub fn propose(env: Env, member: Address, action: AdminAction) -> Result<u32, LibraryError> {
+        let council = Self::require_council_member(&env, &member)?;
+
+        let proposal_id: u32 = env.storage().instance().get(&NEXT_PROPOSAL_KEY).unwrap_or(1);
+        let proposal = Proposal {
+            id: proposal_id,
+            action,
+            proposer: member.clone(),
+            approvals: Vec::from_array(&env, [member.clone()]),
+            expires_at: env.ledger().timestamp() + PROPOSAL_LIFETIME,
+            executed: false,
+        };
+        env.storage()
+            .instance()
+            .set(&NEXT_PROPOSAL_KEY, &(proposal_id + 1));
+
+        // 📣 EVENTO: Los demás miembros del consejo se enteran de la propuesta
+        env.events()
+            .publish((symbol_short!("proposed"), proposal_id), member);
+
+        Self::try_execute_proposal(&env, &council, proposal)?;
+
+        Ok(proposal_id)
+    }
+
+    /// 👍 APROBAR PROPUESTA
+    /// Un miembro del consejo aprueba una propuesta vigente; al alcanzar el umbral
+    /// la operación se ejecuta en la misma transacción
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - member: Miembro del consejo que aprueba (debe firmar)
+    /// - proposal_id: ID de la propuesta
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error
+    pub fn approve_proposal(env: Env, member: Address, proposal_id: u32) -> Result<(), LibraryError> {
+        let council = Self::require_council_member(&env, &member)?;
+
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id).ok_or(LibraryError::ProposalNotFound)?;
+        if proposal.executed {
+            return Err(LibraryError::ProposalAlreadyExecuted);
+        }
+        if env.ledger().timestamp() >= proposal.expires_at {
+            return Err(LibraryError::ProposalExpired);
+        }
+        if proposal.approvals.contains(&member) {
+            return Err(LibraryError::AlreadyApproved);
+        }
+
+        proposal.approvals.push_back(member.clone());
+
+        // 📣 EVENTO: Registro público de cada aprobación
+        env.events()
+            .publish((symbol_short!("endorsed"), proposal_id), member);
+
+        Self::try_execute_proposal(&env, &council, proposal)
+    }
+
+    /// 🔍 OBTENER PROPUESTA
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - proposal_id: ID de la propuesta
+    /// 
+    /// Retorna: Option<Proposal> - Some(proposal) si existe, None si no existe
+    pub fn get_proposal(env: Env, proposal_id: u32) -> Option<Proposal> {
+        Self::load_persistent(&env, &DataKey::Proposal(proposal_id))
+    }
+
+    /// 🔧 FUNCIÓN HELPER: VERIFICAR MIEMBRO DEL CONSEJO
+    /// Exige la firma del miembro y que pertenezca al consejo actual
+    fn require_council_member(env: &Env, member: &Address) -> Result<Council, LibraryError> {
+        member.require_auth();
+
+        let council = Self::get_council(env.clone()).ok_or(LibraryError::Unauthorized)?;
+        if !council.members.contains(member) {
+            return Err(LibraryError::Unauthorized);
+        }
+
+        Ok(council)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: EJECUTAR PROPUESTA SI CORRESPONDE
+    /// Cuenta solo las aprobaciones de miembros del consejo actual (pudo cambiar
+    /// desde que se firmaron); si alcanzan el umbral ejecuta la operación.
+    /// Siempre guarda la propuesta actualizada
+    fn try_execute_proposal(env: &Env, council: &Council, mut proposal: Proposal) -> Result<(), LibraryError> {
+        let valid_approvals = proposal
+            .approvals
+            .iter()
+            .filter(|approver| council.members.contains(approver))
+            .count() as u32;
+
+        if valid_approvals >= council.threshold {
+            Self::execute_action(env, proposal.action.clone())?;
+            proposal.executed = true;
+
+            // 📣 EVENTO: La operación sensible ya se aplicó
+            env.events()
+                .publish((symbol_short!("executed"), proposal.id), proposal.action.clone());
+        }
+
+        Self::save_persistent(env, &DataKey::Proposal(proposal.id), &proposal);
+
+        Ok(())
+    }
+
+    /// 🔧 FUNCIÓN HELPER: EJECUTAR OPERACIÓN SENSIBLE
+    /// Aplica una AdminAction usando los mismos helpers que las funciones de administrador
+    fn execute_action(env: &Env, action: AdminAction) -> Result<(), LibraryError> {
+        match action {
+            AdminAction::SetConfig(config) => Self::apply_set_config(env, config),
+            AdminAction::AddLibrarian(librarian) => Self::apply_add_librarian(env, librarian),
+            AdminAction::RemoveLibrarian(librarian) => Self::apply_remove_librarian(env, librarian),
+            AdminAction::AddBranch(name, branch_admin) => {
+                Self::apply_add_branch(env, name, branch_admin).map(|_| ())
+            }
+            AdminAction::SetBranchAdmin(branch_id, branch_admin) => {
+                Self::apply_set_branch_admin(env, branch_id, branch_admin)
+            }
+            AdminAction::Withdraw(to, amount) => Self::apply_withdraw(env, to, amount),
+            AdminAction::SetCouncil(members, threshold) => {
+                Self::apply_set_council(env, members, threshold)
+            }
+            AdminAction::AddKiosk(public_key) => Self::apply_add_kiosk(env, public_key),
+            AdminAction::RemoveKiosk(public_key) => Self::apply_remove_kiosk(env, public_key),
+            AdminAction::AddBook(branch_id, title, author) => {
+                Self::get_branch(env.clone(), branch_id).ok_or(LibraryError::BranchNotFound)?;
+                Self::create_book(env, title, author, branch_id).map(|_| ())
+            }
+            AdminAction::SetBookRestricted(book_id, restricted) => {
+                Self::apply_set_book_restricted(env, book_id, restricted)
+            }
+        }
+    }
+
+    /// 👪 REGISTRAR DELEGADO
+    /// Un miembro (dependiente) autoriza a un delegado a pedir y devolver libros
+    /// en su nombre. Firman ambos: el dependiente porque los préstamos quedan a su
+    /// nombre y el delegado porque esos préstamos cuentan para su propio límite
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - dependent: Miembro que delega (debe firmar)
+    /// - delegate: Delegado autorizado (debe firmar)
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si se superan los límites
+    pub fn add_delegate(env: Env, dependent: Address, delegate: Address) -> Result<(), LibraryError> {
+        dependent.require_auth();
+        delegate.require_auth();
+
+        if dependent == delegate {
+            return Err(LibraryError::Unauthorized);
+        }
+
+        let delegates = Self::get_delegates(env.clone(), dependent.clone());
+        let dependents = Self::get_dependents(env.clone(), delegate.clone());
+        if delegates.contains(&delegate) {
+            return Ok(());
+        }
+        if delegates.len() >= MAX_DELEGATES || dependents.len() >= MAX_DEPENDENTS {
+            return Err(LibraryError::DelegateLimitReached);
+        }
+
+        // 🔢 LÍMITE: Los préstamos actuales del dependiente pasan a contar para el delegado
+        let dependent_loans = Self::get_active_loans(env.clone(), dependent.clone());
+        if Self::loan_usage(&env, &delegate) + dependent_loans > Self::max_loans(&env) {
+            return Err(LibraryError::LoanLimitReached);
+        }
+
+        Self::update_delegation(&env, &dependent, &delegate, true);
+
+        Ok(())
+    }
+
+    /// 👪 QUITAR DELEGADO
+    /// Cualquiera de las dos partes puede terminar la delegación
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - caller: El dependiente o el delegado (debe firmar)
+    /// - dependent: Miembro que delegó
+    /// - delegate: Delegado a quitar
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si quien llama no es parte
+    pub fn remove_delegate(
+        env: Env,
+        caller: Address,
+        dependent: Address,
+        delegate: Address,
+    ) -> Result<(), LibraryError> {
+        caller.require_auth();
+
+        if caller != dependent && caller != delegate {
+            return Err(LibraryError::Unauthorized);
+        }
+
+        Self::update_delegation(&env, &dependent, &delegate, false);
+
+        Ok(())
+    }
+
+    /// 🔍 OBTENER DELEGADOS DE UN MIEMBRO
+    /// 
+    /// Retorna: Vec<Address> - Delegados que pueden pedir libros por el miembro
+    pub fn get_delegates(env: Env, dependent: Address) -> Vec<Address> {
+        Self::load_persistent(&env, &DataKey::Delegates(dependent)).unwrap_or(Vec::new(&env))
+    }
+
+    /// 🔍 OBTENER DEPENDIENTES DE UN DELEGADO
+    /// 
+    /// Retorna: Vec<Address> - Miembros por los que el delegado puede pedir libros
+    pub fn get_dependents(env: Env, delegate: Address) -> Vec<Address> {
+        Self::load_persistent(&env, &DataKey::Dependents(delegate)).unwrap_or(Vec::new(&env))
+    }
+
+    /// 🔍 OBTENER PRÉSTAMOS ACTIVOS
+    /// 
+    /// Retorna: u32 - Libros que el miembro tiene prestados a su nombre
+    pub fn get_active_loans(env: Env, member: Address) -> u32 {
+        Self::load_persistent(&env, &DataKey::ActiveLoans(member)).unwrap_or(0)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: VERIFICAR DELEGADO
+    /// Exige la firma del delegado y que esté registrado para el dependiente
+    fn require_delegate(env: &Env, delegate: &Address, dependent: &Address) -> Result<(), LibraryError> {
+        delegate.require_auth();
+
+        if !Self::get_delegates(env.clone(), dependent.clone()).contains(delegate) {
+            return Err(LibraryError::Unauthorized);
+        }
+
+        Ok(())
+    }
+
+    /// 🔧 FUNCIÓN HELPER: ACTUALIZAR DELEGACIÓN
+    /// Agrega o quita la relación en las dos listas (delegados y dependientes)
+    fn update_delegation(env: &Env, dependent: &Address, delegate: &Address, add: bool) {
+        let mut delegates = Self::get_delegates(env.clone(), dependent.clone());
+        let mut dependents = Self::get_dependents(env.clone(), delegate.clone());
+
+        if add {
+            delegates.push_back(delegate.clone());
+            dependents.push_back(dependent.clone());
+        } else {
+            if let Some(index) = delegates.first_index_of(delegate) {
+                delegates.remove(index);
+            }
+            if let Some(index) = dependents.first_index_of(dependent) {
+                dependents.remove(index);
+            }
+        }
+
+        Self::save_persistent(env, &DataKey::Delegates(dependent.clone()), &delegates);
+        Self::save_persistent(env, &DataKey::Dependents(delegate.clone()), &dependents);
+    }
+
+    /// 🔧 FUNCIÓN HELPER: LÍMITE DE PRÉSTAMOS
+    /// Usa la configuración si existe; si no, DEFAULT_MAX_LOANS
+    fn max_loans(env: &Env) -> u32 {
+        Self::get_config(env.clone()).map_or(DEFAULT_MAX_LOANS, |config| config.max_loans)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: USO DEL LÍMITE
+    /// Préstamos propios del miembro más los de todos sus dependientes
+    fn loan_usage(env: &Env, member: &Address) -> u32 {
+        let mut usage = Self::get_active_loans(env.clone(), member.clone());
+        for dependent in Self::get_dependents(env.clone(), member.clone()).iter() {
+            usage += Self::get_active_loans(env.clone(), dependent);
+        }
+        usage
+    }
+
+    /// 🔧 FUNCIÓN HELPER: VERIFICAR CAPACIDAD DE PRÉSTAMO
+    /// Un nuevo préstamo a nombre del miembro ocupa un lugar en su propio límite
+    /// y en el de cada uno de sus delegados
+    fn require_loan_capacity(env: &Env, member: &Address) -> Result<(), LibraryError> {
+        let max_loans = Self::max_loans(env);
+
+        if Self::loan_usage(env, member) >= max_loans {
+            return Err(LibraryError::LoanLimitReached);
+        }
+        for delegate in Self::get_delegates(env.clone(), member.clone()).iter() {
+            if Self::loan_usage(env, &delegate) >= max_loans {
+                return Err(LibraryError::LoanLimitReached);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 🔧 FUNCIÓN HELPER: ACTUALIZAR PRÉSTAMOS ACTIVOS
+    /// Suma o resta un préstamo al contador del miembro
+    fn change_active_loans(env: &Env, member: &Address, increase: bool) {
+        let key = DataKey::ActiveLoans(member.clone());
+        let loans: u32 = Self::load_persistent(env, &key).unwrap_or(0);
+        let loans = if increase { loans + 1 } else { loans.saturating_sub(1) };
+        Self::save_persistent(env, &key, &loans);
+    }
+
+    /// 🖥️ REGISTRAR KIOSCO
+    /// Permite al administrador autorizar la llave pública ed25519 de un kiosco
+    /// de autoservicio. Registrar una llave que ya existe (o que se dio de baja)
+    /// conserva su nonce, así las firmas viejas no vuelven a ser válidas
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - public_key: Llave pública ed25519 del kiosco
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si no hay administrador
+    pub fn add_kiosk(env: Env, public_key: BytesN<32>) -> Result<(), LibraryError> {
+        Self::require_admin(&env)?;
+
+        Self::apply_add_kiosk(&env, public_key)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: REGISTRAR KIOSCO
+    /// Aplica el cambio sin verificar permisos (lo hacen add_kiosk() y el consejo)
+    fn apply_add_kiosk(env: &Env, public_key: BytesN<32>) -> Result<(), LibraryError> {
+        env.storage()
+            .instance()
+            .remove(&DataKey::KioskRevoked(public_key.clone()));
+
+        let key = DataKey::Kiosk(public_key);
+        if !env.storage().instance().has(&key) {
+            env.storage().instance().set(&key, &0u64);
+        }
+
+        Ok(())
+    }
+
+    /// 🖥️ DAR DE BAJA KIOSCO
+    /// Permite al administrador revocar la llave de un kiosco (por ejemplo, si fue robado).
+    /// El nonce se conserva: si la llave vuelve a registrarse, sigue desde donde quedó
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - public_key: Llave pública ed25519 del kiosco
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error si no hay administrador
+    pub fn remove_kiosk(env: Env, public_key: BytesN<32>) -> Result<(), LibraryError> {
+        Self::require_admin(&env)?;
+
+        Self::apply_remove_kiosk(&env, public_key)
+    }
+
+    /// 🔧 FUNCIÓN HELPER: DAR DE BAJA KIOSCO
+    /// Aplica el cambio sin verificar permisos (lo hacen remove_kiosk() y el consejo)
+    fn apply_remove_kiosk(env: &Env, public_key: BytesN<32>) -> Result<(), LibraryError> {
+        if env.storage().instance().has(&DataKey::Kiosk(public_key.clone())) {
+            env.storage()
+                .instance()
+                .set(&DataKey::KioskRevoked(public_key), &true);
+        }
+
+        Ok(())
+    }
+
+    /// 🔍 OBTENER NONCE DE UN KIOSCO
+    /// 
+    /// Retorna: Option<u64> - Próximo nonce que debe firmar el kiosco, o None si no está
+    /// registrado o fue dado de baja
+    pub fn get_kiosk_nonce(env: Env, public_key: BytesN<32>) -> Option<u64> {
+        if env
+            .storage()
+            .instance()
+            .has(&DataKey::KioskRevoked(public_key.clone()))
+        {
+            return None;
+        }
+
+        env.storage().instance().get(&DataKey::Kiosk(public_key))
+    }
+
+    /// 🖥️ PRÉSTAMO DESDE UN KIOSCO
+    /// Presta un libro con la autorización firmada off-chain por un kiosco registrado.
+    /// Cualquiera puede enviar la transacción: lo que autoriza el préstamo es la firma
+    /// ed25519 del kiosco sobre (dirección del contrato, payload). Cada nonce se usa una
+    /// sola vez y en orden, para que una firma no pueda repetirse
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - payload: Kiosco, miembro, libro y nonce firmados
+    /// - signature: Firma ed25519 del kiosco
+    /// 
+    /// Retorna: Result<(), LibraryError> - Éxito o error (una firma inválida aborta la transacción)
+    pub fn kiosk_checkout(
+        env: Env,
+        payload: KioskPayload,
+        signature: BytesN<64>,
+    ) -> Result<(), LibraryError> {
+        // 🖥️ KIOSCO: Debe estar registrado por el administrador y no dado de baja
+        let nonce = Self::get_kiosk_nonce(env.clone(), payload.kiosk.clone())
+            .ok_or(LibraryError::KioskNotFound)?;
+
+        // 🔁 REPETICIÓN: Solo aceptamos el próximo nonce
+        if payload.nonce != nonce {
+            return Err(LibraryError::InvalidNonce);
+        }
+
+        // ✍️ FIRMA: El host aborta la transacción si la firma no es válida
+        let message = (env.current_contract_address(), payload.clone()).to_xdr(&env);
+        env.crypto()
+            .ed25519_verify(&payload.kiosk, &message, &signature);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Kiosk(payload.kiosk.clone()), &(nonce + 1));
+
+        // 💰 El miembro no firma la transacción, así que el préstamo no lleva depósito
+        Self::borrow_as(&env, payload.member.clone(), payload.book_id, None)?;
+
+        // 📢 EVENTO: Registramos qué kiosco hizo el préstamo
+        env.events().publish(
+            (symbol_short!("kiosk"), payload.book_id),
+            (payload.kiosk, payload.member),
+        );
+
+        Ok(())
+    }
+
+    /// 🔍 OBTENER PRÉSTAMO ACTIVO
+    /// 
+    /// Retorna: Option<Loan> - El préstamo activo del libro, o None si no está prestado
+    pub fn get_loan(env: Env, book_id: u32) -> Option<Loan> {
+        Self::load_persistent(&env, &DataKey::Loan(book_id))
+    }
+
+    /// ⏰ BARRIDO DE PRÉSTAMOS ATRASADOS
+    /// Revisa un lote de hasta `limit` libros (máximo MAX_SWEEP_BATCH) a partir del
+    /// cursor guardado y emite el evento `overdue` por cada préstamo vencido que aún
+    /// no tuvo recordatorio. Cualquiera puede llamarla (por ejemplo, un bot de avisos);
+    /// llamadas sucesivas continúan donde terminó la anterior y vuelven a empezar al final
+    /// 
+    /// Parámetros:
+    /// - env: Entorno de Soroban
+    /// - limit: Cantidad de libros a revisar
+    /// 
+    /// Retorna: u32 - Cantidad de recordatorios emitidos
+    pub fn sweep_overdue(env: Env, limit: u32) -> u32 {
+        let next_id = Self::get_next_book_id(&env);
+        if next_id == 1 {
+            return 0;
+        }
+
+        let now = env.ledger().timestamp();
+        let mut cursor: u32 = env.storage().instance().get(&SWEEP_CURSOR_KEY).unwrap_or(1);
+        let mut reminders = 0;
+
+        // 🔄 RECORRER EL LOTE: Nunca más libros que los existentes
+        for _ in 0..limit.min(MAX_SWEEP_BATCH).min(next_id - 1) {
+            if cursor >= next_id {
+                cursor = 1;
+            }
+
+            let key = DataKey::Loan(cursor);
+            if let Some(mut loan) = Self::load_persistent::<Loan>(&env, &key) {
+                if !loan.reminded && now > loan.due_at {
+                    // 📢 EVENTO: Un solo recordatorio por préstamo
+                    env.events().publish(
+                        (symbol_short!("overdue"), cursor),
+                        (loan.member.clone(), loan.due_at),
+                    );
+                    loan.reminded = true;
+                    Self::save_persistent(&env, &key, &loan);
+                    reminders += 1;
+                }
+            }
+
+            cursor += 1;
+        }
+
+        env.storage().instance().set(&SWEEP_CURSOR_KEY, &cursor);
+
+        reminders
+    }
+
+    /// 🔧 FUNCIÓN HELPER: OBTENER RESUMEN DE CALIFICACIONES
+    /// Devuelve el resumen guardado o uno vacío si el libro no tiene reseñas
+    fn get_rating_summary(env: &Env, book_id: u32) -> RatingSummary {
+        Self::load_persistent(env, &DataKey::Rating(book_id)).unwrap_or(RatingSummary {
+            count: 0,
+            total_stars: 0,
+            average_x100: 0,
+        })
+    }
+
+    /// 🔧 FUNCIÓN HELPER: GUARDAR DATO DE UN MIEMBRO
+    /// Escribe en persistent storage (crece con cada miembro, no con la instancia)
+    /// y renueva el TTL de la entrada
+    fn save_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+        env.storage().persistent().set(key, value);
+        env.storage()
+            .persistent()
+            .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND);
+    }
+
+    /// 🔧 FUNCIÓN HELPER: LEER DATO DE UN MIEMBRO
+    /// Lee de persistent storage y, si la entrada existe, renueva su TTL
+    fn load_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+        let value = env.storage().persistent().get(key);
+        if value.is_some() {
+            env.storage()
+                .persistent()
+                .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND);
+        }
+        value
+    }
+}
+
+// Incluimos el archivo de tests (separado para mejor organización)
+mod test;
+
+// Tests basados en modelo con secuencias aleatorias de operaciones
+mod test_model;
@@ -1,13 +1,26 @@
 #![cfg(test)]
+// Los tests originales registran el contrato con register_contract (deprecado en el SDK 22)
+#![allow(deprecated)]
+extern crate std;
 
 use super::*;
-use soroban_sdk::{Env, String};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    testutils::{
+        storage::{Persistent, Temporary},
+        Address as _, Events, Ledger, MockAuth, MockAuthInvoke,
+    },
+    token, Address, BytesN, Env, IntoVal, String, Val, Vec,
+};
 
 #[test]
 fn test_add_and_get_book() {
     let env = Env::default();
     let contract_id = env.register_contract(None, LibraryContract);
     let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
 
     // Test: Agregar un libro
     let title = String::from_str(&env, "El Quijote");
@@ -32,6 +45,10 @@ fn test_borrow_book() {
     let env = Env::default();
     let contract_id = env.register_contract(None, LibraryContract);
     let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let member = Address::generate(&env);
 
     // Agregar un libro
     let title = String::from_str(&env, "Cien Años de Soledad");
@@ -39,14 +56,15 @@ fn test_borrow_book() {
     client.add_book(&title, &author);
 
     // Test: Tomar prestado el libro
-    client.borrow_book(&1);
+    client.borrow_book(&member, &1);
 
     // Verificar que el estado cambió
     let book = client.get_book(&1).unwrap();
     assert_eq!(book.status, BookStatus::Borrowed);
+    assert_eq!(book.borrower, Some(member.clone()));
 
     // Test: Intentar tomar prestado un libro ya prestado
-    let result = client.try_borrow_book(&1);
+    let result = client.try_borrow_book(&member, &1);
     assert_eq!(result, Err(Ok(LibraryError::BookNotAvailable)));
 }
 
@@ -55,6 +73,9 @@ fn test_invalid_book_data() {
     let env = Env::default();
     let contract_id = env.register_contract(None, LibraryContract);
     let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
 
     // Test: Intentar agregar libro con datos vacíos
     let empty_title = String::from_str(&env, "");
@@ -69,13 +90,15 @@ fn test_book_not_found() {
     let env = Env::default();
     let contract_id = env.register_contract(None, LibraryContract);
     let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let member = Address::generate(&env);
 
     // Test: Buscar libro que no existe
     let book = client.get_book(&999);
     assert!(book.is_none());
 
     // Test: Intentar tomar prestado libro que no existe
-    let result = client.try_borrow_book(&999);
+    let result = client.try_borrow_book(&member, &999);
     assert_eq!(result, Err(Ok(LibraryError::BookNotFound)));
 }
 
@@ -84,6 +107,10 @@ fn test_get_available_books() {
     let env = Env::default();
     let contract_id = env.register_contract(None, LibraryContract);
     let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let member = Address::generate(&env);
 
     // Agregar varios libros
     client.add_book(&String::from_str(&env, "Libro 1"), &String::from_str(&env, "Autor 1"));
@@ -91,10 +118,10 @@ fn test_get_available_books() {
     client.add_book(&String::from_str(&env, "Libro 3"), &String::from_str(&env, "Autor 3"));
 
     // Tomar prestado uno
-    client.borrow_book(&2);
+    client.borrow_book(&member, &2);
 
     // Test: Obtener libros disponibles
-    let available = client.get_available_books();
+    let available = client.get_available_books(&None);
     assert_eq!(available.len(), 2); // Solo 2 disponibles
 
     // Verificar que son los correctos
@@ -109,26 +136,30 @@ fn test_return_book() {
     let env = Env::default();
     let contract_id = env.register_contract(None, LibraryContract);
     let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let member = Address::generate(&env);
 
     // Agregar y tomar prestado un libro
     let title = String::from_str(&env, "La Casa de los Espíritus");
     let author = String::from_str(&env, "Isabel Allende");
     client.add_book(&title, &author);
-    client.borrow_book(&1);
+    client.borrow_book(&member, &1);
 
     // Verificar que está prestado
     let book = client.get_book(&1).unwrap();
     assert_eq!(book.status, BookStatus::Borrowed);
 
     // Test: Devolver el libro
-    client.return_book(&1);
+    client.return_book(&member, &1);
 
     // Verificar que volvió a estar disponible
     let book = client.get_book(&1).unwrap();
     assert_eq!(book.status, BookStatus::Available);
 
     // Test: Intentar devolver un libro que no está prestado
-    let result = client.try_return_book(&1);
+    let result = client.try_return_book(&member, &1);
     assert_eq!(result, Err(Ok(LibraryError::BookNotAvailable)));
 }
 
@@ -137,6 +168,10 @@ fn test_reserve_book() {
     let env = Env::default();
     let contract_id = env.register_contract(None, LibraryContract);
     let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let member = Address::generate(&env);
 
     // Agregar un libro
     let title = String::from_str(&env, "Rayuela");
@@ -144,14 +179,14 @@ fn test_reserve_book() {
     client.add_book(&title, &author);
 
     // Test: Reservar el libro
-    client.reserve_book(&1);
+    client.reserve_book(&member, &1);
 
     // Verificar que el estado cambió
     let book = client.get_book(&1).unwrap();
     assert_eq!(book.status, BookStatus::Reserved);
 
     // Test: Intentar reservar un libro ya reservado
-    let result = client.try_reserve_book(&1);
+    let result = client.try_reserve_book(&member, &1);
     assert_eq!(result, Err(Ok(LibraryError::BookNotAvailable)));
 }
 
@@ -160,6 +195,10 @@ fn test_get_all_books() {
     let env = Env::default();
     let contract_id = env.register_contract(None, LibraryContract);
     let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let member = Address::generate(&env);
 
     // Test: Sin libros inicialmente
     let all_books = client.get_all_books();
@@ -171,8 +210,8 @@ fn test_get_all_books() {
     client.add_book(&String::from_str(&env, "Libro C"), &String::from_str(&env, "Autor C"));
 
     // Cambiar estados de algunos libros
-    client.borrow_book(&1);
-    client.reserve_book(&2);
+    client.borrow_book(&member, &1);
+    client.reserve_book(&member, &2);
 
     // Test: Obtener todos los libros (independientemente del estado)
     let all_books = client.get_all_books();
@@ -191,4 +230,1289 @@ fn test_get_all_books() {
     
     assert_eq!(book3.id, 3);
     assert_eq!(book3.status, BookStatus::Available);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_rate_book() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let reader_a = Address::generate(&env);
+    let reader_b = Address::generate(&env);
+
+    // Agregar un libro y prestarlo a dos lectores distintos
+    client.add_book(&String::from_str(&env, "Ficciones"), &String::from_str(&env, "Borges"));
+    client.borrow_book(&reader_a, &1);
+    client.return_book(&reader_a, &1);
+    client.borrow_book(&reader_b, &1);
+
+    // Test: Ambos lectores califican el libro
+    let comment = String::from_str(&env, "Imprescindible");
+    client.rate_book(&reader_a, &1, &5, &comment);
+    client.rate_book(&reader_b, &1, &4, &String::from_str(&env, "Muy bueno"));
+
+    // Verificar el resumen agregado junto al libro
+    let details = client.get_book_details(&1);
+    assert_eq!(details.book.id, 1);
+    assert_eq!(details.rating.count, 2);
+    assert_eq!(details.rating.total_stars, 9);
+    assert_eq!(details.rating.average_x100, 450);
+
+    // Verificar que la reseña quedó guardada
+    let review = client.get_review(&1, &reader_a).unwrap();
+    assert_eq!(review.stars, 5);
+    assert_eq!(review.comment, comment);
+
+    // Test: Un miembro no puede calificar dos veces el mismo libro
+    let result = client.try_rate_book(&reader_a, &1, &1, &comment);
+    assert_eq!(result, Err(Ok(LibraryError::AlreadyRated)));
+}
+
+#[test]
+fn test_rate_book_validations() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let reader = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let comment = String::from_str(&env, "Comentario");
+
+    client.add_book(&String::from_str(&env, "Pedro Páramo"), &String::from_str(&env, "Juan Rulfo"));
+    client.borrow_book(&reader, &1);
+
+    // Test: Quien nunca tomó prestado el libro no puede calificarlo
+    let result = client.try_rate_book(&stranger, &1, &3, &comment);
+    assert_eq!(result, Err(Ok(LibraryError::NotBorrowedByMember)));
+
+    // Test: Estrellas fuera de rango
+    let result = client.try_rate_book(&reader, &1, &0, &comment);
+    assert_eq!(result, Err(Ok(LibraryError::InvalidRating)));
+    let result = client.try_rate_book(&reader, &1, &6, &comment);
+    assert_eq!(result, Err(Ok(LibraryError::InvalidRating)));
+
+    // Test: Libro inexistente
+    let result = client.try_rate_book(&reader, &999, &3, &comment);
+    assert_eq!(result, Err(Ok(LibraryError::BookNotFound)));
+
+    // Test: Un libro sin reseñas tiene un resumen vacío
+    let details = client.get_book_details(&1);
+    assert_eq!(details.rating.count, 0);
+    assert_eq!(details.rating.average_x100, 0);
+}
+
+#[test]
+fn test_member_data_in_persistent_storage() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let reader = Address::generate(&env);
+
+    client.add_book(&String::from_str(&env, "Rayuela"), &String::from_str(&env, "Cortázar"));
+    client.borrow_book(&reader, &1);
+    client.rate_book(&reader, &1, &5, &String::from_str(&env, "Genial"));
+
+    // Test: Historial, reseña y resumen viven en persistent storage con TTL renovado
+    let ttl = |key: DataKey| env.as_contract(&contract_id, || env.storage().persistent().get_ttl(&key));
+    assert_eq!(ttl(DataKey::HasBorrowed(reader.clone(), 1)), TTL_EXTEND);
+    assert_eq!(ttl(DataKey::Review(1, reader.clone())), TTL_EXTEND);
+    assert_eq!(ttl(DataKey::Rating(1)), TTL_EXTEND);
+
+    // Test: Leer la reseña renueva su TTL cuando baja del umbral
+    // (la instancia se mantiene viva para poder avanzar el ledger)
+    env.as_contract(&contract_id, || env.storage().instance().extend_ttl(TTL_EXTEND, 2 * TTL_EXTEND));
+    env.ledger().with_mut(|li| li.sequence_number += TTL_EXTEND - TTL_THRESHOLD + 1);
+    assert!(ttl(DataKey::Review(1, reader.clone())) < TTL_THRESHOLD);
+    client.get_review(&1, &reader);
+    assert_eq!(ttl(DataKey::Review(1, reader.clone())), TTL_EXTEND);
+}
+
+#[test]
+fn test_restricted_book_approval() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let librarian = Address::generate(&env);
+    let member = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.add_librarian(&librarian);
+
+    // Agregar una edición rara y marcarla como restringida
+    client.add_book(&String::from_str(&env, "Primera Edición"), &String::from_str(&env, "Anónimo"));
+    client.set_book_restricted(&librarian, &1, &true);
+
+    // Test: Pedir prestado un libro restringido crea una solicitud pendiente
+    client.borrow_book(&member, &1);
+    let book = client.get_book(&1).unwrap();
+    assert_eq!(book.status, BookStatus::Available);
+
+    let request = client.get_request(&1).unwrap();
+    assert_eq!(request.book_id, 1);
+    assert_eq!(request.member, member);
+    assert_eq!(request.status, RequestStatus::Pending);
+    assert_eq!(client.get_pending_requests().len(), 1);
+
+    // Test: No se puede duplicar una solicitud pendiente
+    let result = client.try_borrow_book(&member, &1);
+    assert_eq!(result, Err(Ok(LibraryError::RequestAlreadyPending)));
+
+    // Test: El bibliotecario aprueba y el libro queda prestado
+    client.approve_request(&librarian, &1);
+    let book = client.get_book(&1).unwrap();
+    assert_eq!(book.status, BookStatus::Borrowed);
+    assert_eq!(client.get_request(&1).unwrap().status, RequestStatus::Approved);
+    assert_eq!(client.get_pending_requests().len(), 0);
+
+    // Test: Una solicitud cerrada no puede volver a decidirse
+    let result = client.try_reject_request(&librarian, &1);
+    assert_eq!(result, Err(Ok(LibraryError::RequestNotPending)));
+}
+
+#[test]
+fn test_restricted_book_rejection() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let member = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.add_book(&String::from_str(&env, "Manuscrito"), &String::from_str(&env, "Anónimo"));
+    client.set_book_restricted(&admin, &1, &true);
+    client.borrow_book(&member, &1);
+
+    // Test: Solo bibliotecarios pueden decidir sobre solicitudes
+    let result = client.try_approve_request(&stranger, &1);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+
+    // Test: El administrador rechaza y el libro sigue disponible
+    client.reject_request(&admin, &1);
+    assert_eq!(client.get_request(&1).unwrap().status, RequestStatus::Rejected);
+    assert_eq!(client.get_book(&1).unwrap().status, BookStatus::Available);
+
+    // Test: Tras el rechazo el miembro puede volver a solicitarlo
+    client.borrow_book(&member, &1);
+    assert_eq!(client.get_request(&2).unwrap().status, RequestStatus::Pending);
+
+    // Test: Solicitud inexistente
+    let result = client.try_approve_request(&admin, &99);
+    assert_eq!(result, Err(Ok(LibraryError::RequestNotFound)));
+
+    // Test: La biblioteca no se puede inicializar dos veces
+    let result = client.try_initialize(&stranger);
+    assert_eq!(result, Err(Ok(LibraryError::AlreadyInitialized)));
+}
+
+#[test]
+fn test_change_book_status_requires_librarian() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let member = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.add_book(&String::from_str(&env, "Ficciones"), &String::from_str(&env, "Borges"));
+
+    // Test: Un miembro no puede cambiar estados a mano
+    let result = client.try_change_book_status(&member, &1, &BookStatus::Reserved);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+
+    // Test: Un bibliotecario marca un libro como reservado y lo vuelve a liberar
+    client.change_book_status(&admin, &1, &BookStatus::Reserved);
+    assert_eq!(client.get_book(&1).unwrap().status, BookStatus::Reserved);
+    client.change_book_status(&admin, &1, &BookStatus::Available);
+
+    // Test: Los préstamos no se crean con esta función
+    let result = client.try_change_book_status(&admin, &1, &BookStatus::Borrowed);
+    assert_eq!(result, Err(Ok(LibraryError::BookNotAvailable)));
+
+    // Test: Un préstamo activo no se libera con esta función
+    client.borrow_book(&member, &1);
+    let result = client.try_change_book_status(&admin, &1, &BookStatus::Available);
+    assert_eq!(result, Err(Ok(LibraryError::BookNotAvailable)));
+    assert_eq!(client.get_book(&1).unwrap().borrower, Some(member.clone()));
+    assert_eq!(client.get_active_loans(&member), 1);
+
+    // Test: Un libro prestado sin prestatario registrado no lo puede devolver cualquiera
+    client.add_book(&String::from_str(&env, "El Aleph"), &String::from_str(&env, "Borges"));
+    env.as_contract(&contract_id, || {
+        let mut book: Book = env.storage().instance().get(&2u32).unwrap();
+        book.status = BookStatus::Borrowed;
+        env.storage().instance().set(&2u32, &book);
+    });
+    let result = client.try_return_book(&member, &2);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+    assert_eq!(client.get_active_loans(&member), 1);
+}
+
+#[test]
+fn test_transfer_between_branches() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let north_admin = Address::generate(&env);
+    let south_admin = Address::generate(&env);
+
+    client.initialize(&admin);
+    let north = client.add_branch(&String::from_str(&env, "Norte"), &north_admin);
+    let south = client.add_branch(&String::from_str(&env, "Sur"), &south_admin);
+    assert_eq!(client.get_branch(&north).unwrap().admin, north_admin);
+
+    // Un libro en la sede principal y otro en la sede norte
+    client.add_book(&String::from_str(&env, "Libro 1"), &String::from_str(&env, "Autor 1"));
+    // Test: Agregar a la sede principal requiere la firma de su administrador
+    assert_eq!(env.auths()[0].0, admin);
+    let book_id = client.add_book_to_branch(&north, &String::from_str(&env, "Libro 2"), &String::from_str(&env, "Autor 2"));
+    let book = client.get_book(&book_id).unwrap();
+    assert_eq!(book.home_branch, north);
+    assert_eq!(book.current_branch, north);
+
+    // Test: Filtrar disponibles por sede
+    assert_eq!(client.get_available_books(&Some(north)).len(), 1);
+    assert_eq!(client.get_available_books(&Some(south)).len(), 0);
+    assert_eq!(client.get_available_books(&None).len(), 2);
+
+    // Test: Enviar el libro de norte a sur
+    client.transfer_book(&book_id, &north, &south);
+    let book = client.get_book(&book_id).unwrap();
+    assert_eq!(book.status, BookStatus::InTransit);
+    assert_eq!(book.in_transit_to, Some(south));
+    assert_eq!(client.get_available_books(&Some(north)).len(), 0);
+
+    // Test: Un libro en tránsito no se puede prestar
+    let member = Address::generate(&env);
+    let result = client.try_borrow_book(&member, &book_id);
+    assert_eq!(result, Err(Ok(LibraryError::BookNotAvailable)));
+
+    // Test: La sede sur recibe el libro
+    client.receive_book(&book_id);
+    let book = client.get_book(&book_id).unwrap();
+    assert_eq!(book.status, BookStatus::Available);
+    assert_eq!(book.home_branch, north);
+    assert_eq!(book.current_branch, south);
+    assert_eq!(client.get_available_books(&Some(south)).len(), 1);
+}
+
+#[test]
+fn test_invalid_transfers() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+
+    client.initialize(&admin);
+    let north = client.add_branch(&String::from_str(&env, "Norte"), &admin);
+    client.add_book(&String::from_str(&env, "Libro"), &String::from_str(&env, "Autor"));
+
+    // Test: Origen distinto de la sede actual del libro
+    let result = client.try_transfer_book(&1, &north, &0);
+    assert_eq!(result, Err(Ok(LibraryError::InvalidTransfer)));
+
+    // Test: Origen y destino iguales
+    let result = client.try_transfer_book(&1, &0, &0);
+    assert_eq!(result, Err(Ok(LibraryError::InvalidTransfer)));
+
+    // Test: Sede inexistente
+    let result = client.try_transfer_book(&1, &0, &42);
+    assert_eq!(result, Err(Ok(LibraryError::BranchNotFound)));
+
+    // Test: No se puede recibir un libro que no está en tránsito
+    let result = client.try_receive_book(&1);
+    assert_eq!(result, Err(Ok(LibraryError::InvalidTransfer)));
+}
+
+#[test]
+fn test_inter_library_loan() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    // Dos instancias independientes del contrato: la biblioteca que pide y la que presta
+    let borrower_id = env.register(LibraryContract, ());
+    let lender_id = env.register(LibraryContract, ());
+    let borrower_library = LibraryContractClient::new(&env, &borrower_id);
+    let lender_library = LibraryContractClient::new(&env, &lender_id);
+
+    let librarian = Address::generate(&env);
+    borrower_library.initialize(&librarian);
+    lender_library.initialize(&Address::generate(&env));
+    lender_library.add_book(&String::from_str(&env, "Ensayo sobre la ceguera"), &String::from_str(&env, "Saramago"));
+
+    // Test: Pedir prestado el libro a la otra biblioteca
+    borrower_library.borrow_from_partner(&librarian, &lender_id, &1);
+
+    // En la biblioteca que presta, el libro figura a nombre del contrato que lo pidió
+    let book = lender_library.get_book(&1).unwrap();
+    assert_eq!(book.status, BookStatus::Borrowed);
+    assert_eq!(book.borrower, Some(borrower_id.clone()));
+    assert!(borrower_library.get_partner_loan(&lender_id, &1).is_some());
+
+    // Test: Nadie más puede devolverlo
+    let stranger = Address::generate(&env);
+    let result = lender_library.try_return_book(&stranger, &1);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+
+    // Test: Los errores de la biblioteca que presta se propagan
+    let result = borrower_library.try_borrow_from_partner(&librarian, &lender_id, &1);
+    assert_eq!(result, Err(Ok(LibraryError::BookNotAvailable)));
+
+    // Test: Devolver el libro mediante otra llamada entre contratos
+    borrower_library.return_to_partner(&librarian, &lender_id, &1);
+    let book = lender_library.get_book(&1).unwrap();
+    assert_eq!(book.status, BookStatus::Available);
+    assert_eq!(book.borrower, None);
+    assert!(borrower_library.get_partner_loan(&lender_id, &1).is_none());
+
+    // Test: No se puede devolver un préstamo que ya no existe
+    let result = borrower_library.try_return_to_partner(&librarian, &lender_id, &1);
+    assert_eq!(result, Err(Ok(LibraryError::PartnerLoanNotFound)));
+}
+
+#[test]
+fn test_owned_book_transfers() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+
+    // Test: Alice registra un ejemplar propio
+    let book_id = client.mint_book(&alice, &String::from_str(&env, "Rayuela"), &String::from_str(&env, "Cortázar"));
+    assert_eq!(client.owner_of(&book_id), alice);
+    assert_eq!(client.balance_of(&alice), 1);
+
+    // Test: Alice transfiere el libro a Bob
+    client.transfer(&alice, &bob, &book_id);
+    assert_eq!(client.owner_of(&book_id), bob);
+    assert_eq!(client.balance_of(&alice), 0);
+    assert_eq!(client.balance_of(&bob), 1);
+
+    // Test: Alice ya no puede transferirlo
+    let result = client.try_transfer(&alice, &carol, &book_id);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+
+    // Test: Bob aprueba a Carol, que transfiere el libro a sí misma
+    client.approve(&bob, &carol, &book_id);
+    assert_eq!(client.get_approved(&book_id), Some(carol.clone()));
+    client.transfer_from(&carol, &bob, &carol, &book_id);
+    assert_eq!(client.owner_of(&book_id), carol);
+    assert_eq!(client.get_approved(&book_id), None);
+
+    // Test: Los libros de la biblioteca no tienen dueño
+    client.add_book(&String::from_str(&env, "Catálogo"), &String::from_str(&env, "Biblioteca"));
+    let result = client.try_owner_of(&2);
+    assert_eq!(result, Err(Ok(LibraryError::BookNotOwned)));
+}
+
+#[test]
+fn test_lend_owned_book() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let borrower = Address::generate(&env);
+
+    let book_id = client.mint_book(&owner, &String::from_str(&env, "Aura"), &String::from_str(&env, "Fuentes"));
+
+    // Test: Nadie puede tomar prestado un libro con dueño por su cuenta
+    let result = client.try_borrow_book(&borrower, &book_id);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+
+    // Test: El dueño presta el libro
+    client.lend_owned_book(&owner, &borrower, &book_id);
+    let book = client.get_book(&book_id).unwrap();
+    assert_eq!(book.status, BookStatus::Borrowed);
+    assert_eq!(book.borrower, Some(borrower.clone()));
+
+    // Test: El prestatario no puede transferir el libro
+    let result = client.try_transfer(&borrower, &borrower, &book_id);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+
+    // Test: Ni siquiera el dueño puede transferirlo mientras está prestado
+    let result = client.try_transfer(&owner, &borrower, &book_id);
+    assert_eq!(result, Err(Ok(LibraryError::BookOnLoan)));
+
+    // Test: Tras la devolución el dueño vuelve a poder transferirlo
+    client.return_book(&borrower, &book_id);
+    client.transfer(&owner, &borrower, &book_id);
+    assert_eq!(client.owner_of(&book_id), borrower);
+}
+
+#[test]
+fn test_membership_subscription() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let member = Address::generate(&env);
+
+    // Token de pago y saldo inicial del miembro
+    let token_id = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    token::StellarAssetClient::new(&env, &token_id).mint(&member, &1_000);
+    let token_client = token::Client::new(&env, &token_id);
+
+    // Configuración: membresía de 100 unidades por período de 30 días
+    let period = 30 * 24 * 60 * 60;
+    client.initialize(&admin);
+    client.set_config(&LibraryConfig {
+        token: token_id.clone(),
+        late_fee_per_day: 5,
+        deposit: 50,
+        replacement_cost: 500,
+        membership_price: 100,
+        membership_period: period,
+        max_loans: 5,
+    });
+    client.add_book(&String::from_str(&env, "Libro 1"), &String::from_str(&env, "Autor 1"));
+    client.add_book(&String::from_str(&env, "Libro 2"), &String::from_str(&env, "Autor 2"));
+    env.ledger().set_timestamp(1_000);
+
+    // Test: Sin membresía no se puede prestar ni reservar
+    let result = client.try_borrow_book(&member, &1);
+    assert_eq!(result, Err(Ok(LibraryError::MembershipExpired)));
+    let result = client.try_reserve_book(&member, &1);
+    assert_eq!(result, Err(Ok(LibraryError::MembershipExpired)));
+
+    // Test: Suscribirse por dos períodos cobra 200 unidades
+    let membership = client.subscribe(&member, &2);
+    assert_eq!(membership.expires_at, 1_000 + 2 * period);
+    assert_eq!(token_client.balance(&member), 800);
+    assert_eq!(token_client.balance(&contract_id), 200);
+    client.borrow_book(&member, &1);
+
+    // Test: La renovación se suma al vencimiento actual
+    env.ledger().set_timestamp(1_000 + period);
+    let membership = client.renew_membership(&member, &1);
+    assert_eq!(membership.expires_at, 1_000 + 3 * period);
+    assert_eq!(client.get_membership(&member), Some(membership));
+
+    // Test: Una vez vencida, prestar y reservar fallan
+    env.ledger().set_timestamp(1_000 + 3 * period);
+    let result = client.try_reserve_book(&member, &2);
+    assert_eq!(result, Err(Ok(LibraryError::MembershipExpired)));
+
+    // Test: Al suscribirse de nuevo se cuenta desde ahora
+    let membership = client.subscribe(&member, &1);
+    assert_eq!(membership.expires_at, 1_000 + 4 * period);
+    client.reserve_book(&member, &2);
+
+    // Test: La membresía vive en persistent storage con TTL renovado
+    let ttl = env.as_contract(&contract_id, || {
+        env.storage().persistent().get_ttl(&DataKey::Membership(member.clone()))
+    });
+    assert_eq!(ttl, TTL_EXTEND);
+
+    // Test: El administrador retira los pagos de la tesorería
+    // (quedan 150 de membresías y el depósito de 50 del libro prestado)
+    let treasurer = Address::generate(&env);
+    client.withdraw(&treasurer, &250);
+    assert_eq!(token_client.balance(&treasurer), 250);
+    assert_eq!(token_client.balance(&contract_id), 200);
+}
+
+#[test]
+fn test_library_config() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let member = Address::generate(&env);
+    let token_id = env.register_stellar_asset_contract_v2(admin.clone()).address();
+
+    client.initialize(&admin);
+
+    // Test: Sin configuración no hay suscripción (y la biblioteca es gratuita)
+    assert_eq!(client.get_config(), None);
+    let result = client.try_subscribe(&member, &1);
+    assert_eq!(result, Err(Ok(LibraryError::MembershipNotConfigured)));
+
+    // Test: Guardar y leer la configuración
+    let config = LibraryConfig {
+        token: token_id.clone(),
+        late_fee_per_day: 10,
+        deposit: 100,
+        replacement_cost: 1_000,
+        membership_price: 0,
+        membership_period: 7 * 24 * 60 * 60,
+        max_loans: 3,
+    };
+    client.set_config(&config);
+
+    // Test: Cada cambio de configuración emite un evento con el valor anterior y el nuevo
+    let expected_event = (
+        contract_id.clone(),
+        (symbol_short!("config"),).into_val(&env),
+        (None::<LibraryConfig>, config.clone()).into_val(&env),
+    );
+    assert_eq!(env.events().all(), soroban_sdk::vec![&env, expected_event]);
+    assert_eq!(client.get_config(), Some(config.clone()));
+
+    // Test: Valores fuera de rango
+    let mut invalid = config.clone();
+    invalid.membership_price = -1;
+    assert_eq!(client.try_set_config(&invalid), Err(Ok(LibraryError::InvalidConfig)));
+    let mut invalid = config.clone();
+    invalid.deposit = 2_000;
+    assert_eq!(client.try_set_config(&invalid), Err(Ok(LibraryError::InvalidConfig)));
+    let mut invalid = config.clone();
+    invalid.membership_period = 0;
+    assert_eq!(client.try_set_config(&invalid), Err(Ok(LibraryError::InvalidConfig)));
+    let mut invalid = config.clone();
+    invalid.max_loans = 0;
+    assert_eq!(client.try_set_config(&invalid), Err(Ok(LibraryError::InvalidConfig)));
+    assert_eq!(client.get_config(), Some(config));
+
+    // Test: Cero períodos o renovar sin membresía previa
+    let result = client.try_subscribe(&member, &0);
+    assert_eq!(result, Err(Ok(LibraryError::InvalidPeriod)));
+    let result = client.try_renew_membership(&member, &1);
+    assert_eq!(result, Err(Ok(LibraryError::MembershipExpired)));
+}
+
+#[test]
+fn test_loan_fees() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let member = Address::generate(&env);
+    let token_id = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    token::StellarAssetClient::new(&env, &token_id).mint(&member, &1_000);
+    let token_client = token::Client::new(&env, &token_id);
+    let day = 24 * 60 * 60;
+
+    // Configuración: depósito de 50, multa de 5 por día y reposición de 100
+    client.initialize(&admin);
+    client.set_config(&LibraryConfig {
+        token: token_id.clone(),
+        late_fee_per_day: 5,
+        deposit: 50,
+        replacement_cost: 100,
+        membership_price: 0,
+        membership_period: 365 * day,
+        max_loans: 5,
+    });
+    client.add_book(&String::from_str(&env, "Libro 1"), &String::from_str(&env, "Autor 1"));
+    client.add_book(&String::from_str(&env, "Libro 2"), &String::from_str(&env, "Autor 2"));
+    client.subscribe(&member, &1);
+
+    // Test: Prestar cobra el depósito, que no se puede retirar de la tesorería
+    client.borrow_book(&member, &1);
+    assert_eq!(client.get_loan(&1).unwrap().deposit, 50);
+    assert_eq!(token_client.balance(&member), 950);
+    let result = client.try_withdraw(&admin, &1);
+    assert_eq!(result, Err(Ok(LibraryError::DepositsLocked)));
+
+    // Test: Con depósitos retenidos no se puede cambiar de token (sí el resto de la tabla)
+    let mut config = client.get_config().unwrap();
+    config.token = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let result = client.try_set_config(&config);
+    assert_eq!(result, Err(Ok(LibraryError::DepositsLocked)));
+    config.token = token_id.clone();
+    client.set_config(&config);
+
+    // Test: Devolver a tiempo reintegra el depósito completo
+    client.return_book(&member, &1);
+    assert_eq!(token_client.balance(&member), 1_000);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    // Test: Tres días (o fracción) de atraso se descuentan del depósito
+    client.borrow_book(&member, &1);
+    env.ledger().with_mut(|li| li.timestamp += LOAN_PERIOD + 2 * day + 1);
+    client.return_book(&member, &1);
+    assert_eq!(token_client.balance(&member), 985);
+    assert_eq!(token_client.balance(&contract_id), 15);
+
+    // Test: La multa nunca supera el costo de reposición; lo que falta se cobra aparte
+    client.borrow_book(&member, &1);
+    env.ledger().with_mut(|li| li.timestamp += LOAN_PERIOD + 100 * day);
+    client.return_book(&member, &1);
+    assert_eq!(token_client.balance(&member), 885);
+    assert_eq!(token_client.balance(&contract_id), 115);
+
+    // Test: Un libro perdido cuesta la reposición y sale del catálogo
+    client.borrow_book(&member, &2);
+    client.report_lost(&member, &2);
+    assert_eq!(token_client.balance(&member), 785);
+    assert_eq!(client.get_book(&2), None);
+    assert_eq!(client.get_loan(&2), None);
+    assert_eq!(client.get_active_loans(&member), 0);
+    let result = client.try_report_lost(&member, &1);
+    assert_eq!(result, Err(Ok(LibraryError::BookNotAvailable)));
+
+    // Test: Sin depósitos retenidos, toda la tesorería se puede retirar
+    client.withdraw(&admin, &215);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    // Test: Y el token se puede cambiar
+    config.token = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    client.set_config(&config);
+}
+
+/// Configuración de los tests de cobros: depósito de 50, multa de 5 por día,
+/// reposición de 100 y membresía gratuita por un año
+fn fee_config(token: &Address) -> LibraryConfig {
+    LibraryConfig {
+        token: token.clone(),
+        late_fee_per_day: 5,
+        deposit: 50,
+        replacement_cost: 100,
+        membership_price: 0,
+        membership_period: 365 * 24 * 60 * 60,
+        max_loans: 5,
+    }
+}
+
+/// Autoriza solo la firma de `signer` sobre una llamada al contrato (sin mock_all_auths)
+/// y, si hay `payment` (token, monto), la transferencia que el contrato hace desde `signer`
+fn authorize_only(
+    env: &Env,
+    signer: &Address,
+    contract: &Address,
+    fn_name: &str,
+    args: Vec<Val>,
+    payment: Option<(&Address, i128)>,
+) {
+    let transfer = payment.map(|(token, amount)| MockAuthInvoke {
+        contract: token,
+        fn_name: "transfer",
+        args: (signer, contract, amount).into_val(env),
+        sub_invokes: &[],
+    });
+    env.mock_auths(&[MockAuth {
+        address: signer,
+        invoke: &MockAuthInvoke {
+            contract,
+            fn_name,
+            args,
+            sub_invokes: transfer.as_slice(),
+        },
+    }]);
+}
+
+#[test]
+fn test_delegated_loan_payments() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let parent = Address::generate(&env);
+    let child = Address::generate(&env);
+    let token_id = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    token::StellarAssetClient::new(&env, &token_id).mint(&parent, &1_000);
+    let token_client = token::Client::new(&env, &token_id);
+
+    client.initialize(&admin);
+    client.set_config(&fee_config(&token_id));
+    client.add_book(&String::from_str(&env, "El Principito"), &String::from_str(&env, "Saint-Exupéry"));
+    client.subscribe(&parent, &1);
+    client.subscribe(&child, &1);
+    client.add_delegate(&child, &parent);
+
+    // Test: Solo firma el delegado, que deja el depósito por su dependiente
+    let args = (&parent, &child, 1u32).into_val(&env);
+    authorize_only(&env, &parent, &contract_id, "borrow_book_for", args, Some((&token_id, 50)));
+    client.borrow_book_for(&parent, &child, &1);
+    let loan = client.get_loan(&1).unwrap();
+    assert_eq!(loan.member, child);
+    assert_eq!(loan.payer, Some(parent.clone()));
+    assert_eq!(token_client.balance(&parent), 950);
+
+    // Test: Con mucho atraso el delegado paga lo que el depósito no cubre
+    env.ledger().with_mut(|li| li.timestamp += LOAN_PERIOD + 100 * 24 * 60 * 60);
+    let args = (&parent, &child, 1u32).into_val(&env);
+    authorize_only(&env, &parent, &contract_id, "return_book_for", args, Some((&token_id, 50)));
+    client.return_book_for(&parent, &child, &1);
+    assert_eq!(token_client.balance(&parent), 900);
+    assert_eq!(token_client.balance(&contract_id), 100);
+    assert_eq!(client.get_active_loans(&child), 0);
+
+    // Test: A tiempo, el depósito vuelve al delegado aunque devuelva el propio miembro
+    let args = (&parent, &child, 1u32).into_val(&env);
+    authorize_only(&env, &parent, &contract_id, "borrow_book_for", args, Some((&token_id, 50)));
+    client.borrow_book_for(&parent, &child, &1);
+    authorize_only(&env, &child, &contract_id, "return_book", (&child, 1u32).into_val(&env), None);
+    client.return_book(&child, &1);
+    assert_eq!(token_client.balance(&parent), 900);
+    assert_eq!(token_client.balance(&child), 0);
+}
+
+#[test]
+fn test_loans_without_member_signature() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let member = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let token_id = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    token::StellarAssetClient::new(&env, &token_id).mint(&member, &1_000);
+    let token_client = token::Client::new(&env, &token_id);
+    let kiosk_key = SigningKey::from_bytes(&[7; 32]);
+    let kiosk = BytesN::from_array(&env, &kiosk_key.verifying_key().to_bytes());
+
+    client.initialize(&admin);
+    client.set_config(&fee_config(&token_id));
+    client.add_kiosk(&kiosk);
+    client.subscribe(&member, &1);
+    client.add_book(&String::from_str(&env, "Primera Edición"), &String::from_str(&env, "Anónimo"));
+    client.add_book(&String::from_str(&env, "Rayuela"), &String::from_str(&env, "Cortázar"));
+    client.set_book_restricted(&admin, &1, &true);
+    let owned = client.mint_book(&owner, &String::from_str(&env, "Aura"), &String::from_str(&env, "Fuentes"));
+    client.borrow_book(&member, &1);
+
+    // Test: Al aprobar una solicitud firma solo el bibliotecario y no hay depósito
+    authorize_only(&env, &admin, &contract_id, "approve_request", (&admin, 1u32).into_val(&env), None);
+    client.approve_request(&admin, &1);
+    let loan = client.get_loan(&1).unwrap();
+    assert_eq!((loan.deposit, loan.payer), (0, None));
+
+    // Test: Un préstamo de kiosco no lleva ninguna firma ni depósito
+    let payload = KioskPayload { kiosk, member: member.clone(), book_id: 2, nonce: 0 };
+    let signature = sign_kiosk_payload(&env, &contract_id, &kiosk_key, &payload);
+    env.mock_auths(&[]);
+    client.kiosk_checkout(&payload, &signature);
+    assert_eq!(client.get_loan(&2).unwrap().deposit, 0);
+
+    // Test: El dueño presta su libro sin la firma del prestatario y sin depósito
+    let args = (&owner, &member, owned).into_val(&env);
+    authorize_only(&env, &owner, &contract_id, "lend_owned_book", args, None);
+    client.lend_owned_book(&owner, &member, &owned);
+    assert_eq!(client.get_loan(&owned).unwrap().deposit, 0);
+    assert_eq!(token_client.balance(&member), 1_000);
+
+    // Test: Al devolver, el miembro firma y paga la multa completa si hubo atraso
+    env.ledger().with_mut(|li| li.timestamp += LOAN_PERIOD + 1);
+    authorize_only(&env, &member, &contract_id, "return_book", (&member, 1u32).into_val(&env), Some((&token_id, 5)));
+    client.return_book(&member, &1);
+    assert_eq!(token_client.balance(&member), 995);
+    assert_eq!(token_client.balance(&contract_id), 5);
+}
+
+#[test]
+fn test_inter_library_loan_payments() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let borrower_id = env.register(LibraryContract, ());
+    let lender_id = env.register(LibraryContract, ());
+    let borrower_library = LibraryContractClient::new(&env, &borrower_id);
+    let lender_library = LibraryContractClient::new(&env, &lender_id);
+    let admin = Address::generate(&env);
+    let librarian = Address::generate(&env);
+    let token_id = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    token::StellarAssetClient::new(&env, &token_id).mint(&borrower_id, &1_000);
+    let token_client = token::Client::new(&env, &token_id);
+
+    // La biblioteca que presta cobra depósito; la que pide es miembro de ella
+    borrower_library.initialize(&librarian);
+    lender_library.initialize(&admin);
+    lender_library.set_config(&fee_config(&token_id));
+    lender_library.subscribe(&borrower_id, &1);
+    lender_library.add_book(&String::from_str(&env, "Ensayo sobre la ceguera"), &String::from_str(&env, "Saramago"));
+
+    // Test: Firma solo el bibliotecario; el depósito sale de la tesorería de la biblioteca
+    let args = (&librarian, &lender_id, 1u32).into_val(&env);
+    authorize_only(&env, &librarian, &borrower_id, "borrow_from_partner", args, None);
+    borrower_library.borrow_from_partner(&librarian, &lender_id, &1);
+    assert_eq!(lender_library.get_loan(&1).unwrap().payer, Some(borrower_id.clone()));
+    assert_eq!(token_client.balance(&borrower_id), 950);
+    assert_eq!(token_client.balance(&lender_id), 50);
+
+    // Test: Con atraso, la multa que el depósito no cubre también se autoriza sola
+    env.ledger().with_mut(|li| li.timestamp += LOAN_PERIOD + 100 * 24 * 60 * 60);
+    let args = (&librarian, &lender_id, 1u32).into_val(&env);
+    authorize_only(&env, &librarian, &borrower_id, "return_to_partner", args, None);
+    borrower_library.return_to_partner(&librarian, &lender_id, &1);
+    assert_eq!(token_client.balance(&borrower_id), 900);
+    assert_eq!(token_client.balance(&lender_id), 100);
+    assert_eq!(lender_library.get_book(&1).unwrap().status, BookStatus::Available);
+}
+
+#[test]
+fn test_book_donations() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    client.initialize(&admin);
+
+    // Test: Un miembro propone dos donaciones
+    let title = String::from_str(&env, "Los detectives salvajes");
+    let author = String::from_str(&env, "Bolaño");
+    let first = client.propose_donation(&donor, &title, &author);
+    let second = client.propose_donation(&donor, &String::from_str(&env, "Libro repetido"), &author);
+    assert_eq!(client.get_donation(&first).unwrap().status, DonationStatus::Pending);
+    assert_eq!(client.get_all_books().len(), 0);
+
+    // Test: El bibliotecario acepta la primera, que pasa a ser un libro del catálogo
+    let book_id = client.accept_donation(&admin, &first);
+    let book = client.get_book(&book_id).unwrap();
+    assert_eq!(book.title, title);
+    assert_eq!(book.donor, Some(donor.clone()));
+    assert_eq!(book.status, BookStatus::Available);
+
+    // Test: El bibliotecario rechaza la segunda
+    client.reject_donation(&admin, &second);
+
+    // Test: El donante ve sus donaciones con su estado
+    let donations = client.get_donations(&donor);
+    assert_eq!(donations.len(), 2);
+    let accepted = donations.get(0).unwrap();
+    assert_eq!(accepted.status, DonationStatus::Accepted);
+    assert_eq!(accepted.book_id, Some(book_id));
+    let rejected = donations.get(1).unwrap();
+    assert_eq!(rejected.status, DonationStatus::Rejected);
+    assert_eq!(rejected.book_id, None);
+
+    // Test: Las donaciones y la lista del donante viven en persistent storage
+    let ttl = |key: DataKey| env.as_contract(&contract_id, || env.storage().persistent().get_ttl(&key));
+    assert_eq!(ttl(DataKey::Donation(second)), TTL_EXTEND);
+    assert_eq!(ttl(DataKey::DonorDonations(donor.clone())), TTL_EXTEND);
+
+    // Test: Una donación decidida no se puede volver a decidir
+    let result = client.try_accept_donation(&admin, &second);
+    assert_eq!(result, Err(Ok(LibraryError::DonationNotPending)));
+    let result = client.try_reject_donation(&admin, &99);
+    assert_eq!(result, Err(Ok(LibraryError::DonationNotFound)));
+
+    // Test: Datos vacíos
+    let result = client.try_propose_donation(&donor, &String::from_str(&env, ""), &author);
+    assert_eq!(result, Err(Ok(LibraryError::InvalidBookData)));
+}
+
+#[test]
+fn test_council_proposals() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let librarian = Address::generate(&env);
+
+    // Consejo 2-de-3
+    client.initialize(&admin);
+    let members = soroban_sdk::vec![&env, alice.clone(), bob.clone(), carol.clone()];
+    client.set_council(&members, &2);
+    assert_eq!(client.get_council().unwrap().threshold, 2);
+
+    // Test: El administrador ya no puede actuar solo
+    let result = client.try_add_librarian(&librarian);
+    assert_eq!(result, Err(Ok(LibraryError::CouncilRequired)));
+
+    // Test: Una propuesta con una sola aprobación no se ejecuta
+    let action = AdminAction::AddLibrarian(librarian.clone());
+    let proposal_id = client.propose(&alice, &action);
+    let proposal = client.get_proposal(&proposal_id).unwrap();
+    assert!(!proposal.executed);
+    assert_eq!(proposal.approvals.len(), 1);
+
+    // Test: Nadie puede aprobar dos veces, ni quien no es del consejo
+    let result = client.try_approve_proposal(&alice, &proposal_id);
+    assert_eq!(result, Err(Ok(LibraryError::AlreadyApproved)));
+    let result = client.try_approve_proposal(&librarian, &proposal_id);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+
+    // Test: La segunda aprobación alcanza el umbral y ejecuta la operación
+    client.approve_proposal(&bob, &proposal_id);
+    assert!(client.get_proposal(&proposal_id).unwrap().executed);
+
+    // Test: Las propuestas viven en persistent storage con TTL renovado
+    let ttl = env.as_contract(&contract_id, || {
+        env.storage().persistent().get_ttl(&DataKey::Proposal(proposal_id))
+    });
+    assert_eq!(ttl, TTL_EXTEND);
+
+    // Test: El catálogo también cambia solo mediante propuestas
+    let title = String::from_str(&env, "Libro");
+    let author = String::from_str(&env, "Autor");
+    let result = client.try_add_book(&title, &author);
+    assert_eq!(result, Err(Ok(LibraryError::CouncilRequired)));
+    let result = client.try_add_book_to_branch(&0, &title, &author);
+    assert_eq!(result, Err(Ok(LibraryError::CouncilRequired)));
+    let add_id = client.propose(&alice, &AdminAction::AddBook(0, title.clone(), author));
+    client.approve_proposal(&carol, &add_id);
+    assert_eq!(client.get_book(&1).unwrap().title, title);
+
+    let donor = Address::generate(&env);
+    let donation_id = client.propose_donation(&donor, &title, &title);
+    let result = client.try_accept_donation(&librarian, &donation_id);
+    assert_eq!(result, Err(Ok(LibraryError::CouncilRequired)));
+    let result = client.try_mint_book(&donor, &title, &title);
+    assert_eq!(result, Err(Ok(LibraryError::CouncilRequired)));
+    assert_eq!(client.get_all_books().len(), 1);
+
+    let result = client.try_set_book_restricted(&librarian, &1, &true);
+    assert_eq!(result, Err(Ok(LibraryError::CouncilRequired)));
+    let restrict_id = client.propose(&bob, &AdminAction::SetBookRestricted(1, true));
+    client.approve_proposal(&carol, &restrict_id);
+    assert!(client.get_book(&1).unwrap().restricted);
+
+    // Test: Una propuesta ejecutada no se vuelve a aprobar
+    let result = client.try_approve_proposal(&carol, &proposal_id);
+    assert_eq!(result, Err(Ok(LibraryError::ProposalAlreadyExecuted)));
+}
+
+#[test]
+fn test_council_proposal_expiry() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    client.initialize(&admin);
+
+    // Test: Consejos inválidos (umbral imposible o miembros repetidos)
+    let members = soroban_sdk::vec![&env, alice.clone(), bob.clone()];
+    let result = client.try_set_council(&members, &3);
+    assert_eq!(result, Err(Ok(LibraryError::InvalidCouncil)));
+    let repeated = soroban_sdk::vec![&env, alice.clone(), alice.clone()];
+    let result = client.try_set_council(&repeated, &2);
+    assert_eq!(result, Err(Ok(LibraryError::InvalidCouncil)));
+
+    client.set_council(&members, &2);
+
+    // Test: Una propuesta vencida ya no puede aprobarse
+    let action = AdminAction::AddBranch(String::from_str(&env, "Norte"), alice.clone());
+    let proposal_id = client.propose(&alice, &action);
+    env.ledger().set_timestamp(8 * 24 * 60 * 60);
+    let result = client.try_approve_proposal(&bob, &proposal_id);
+    assert_eq!(result, Err(Ok(LibraryError::ProposalExpired)));
+    assert_eq!(client.get_branch(&1), None);
+
+    // Test: El consejo también se cambia a sí mismo por propuesta
+    let action = AdminAction::SetCouncil(soroban_sdk::vec![&env, bob.clone()], 1);
+    let proposal_id = client.propose(&alice, &action);
+    client.approve_proposal(&bob, &proposal_id);
+    assert_eq!(client.get_council().unwrap().members.len(), 1);
+
+    // Test: Con umbral 1 la propuesta se ejecuta al crearla
+    let action = AdminAction::AddBranch(String::from_str(&env, "Sur"), bob.clone());
+    client.propose(&bob, &action);
+    assert!(client.get_branch(&1).is_some());
+    let result = client.try_propose(&alice, &action);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+}
+
+#[test]
+fn test_delegated_borrowing() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let parent = Address::generate(&env);
+    let child = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    client.add_book(&String::from_str(&env, "El Principito"), &String::from_str(&env, "Saint-Exupéry"));
+
+    // Test: Sin delegación nadie puede pedir libros por otro
+    let result = client.try_borrow_book_for(&parent, &child, &1);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+
+    // Test: El hijo registra a su madre/padre como delegado
+    client.add_delegate(&child, &parent);
+    assert_eq!(client.get_delegates(&child), soroban_sdk::vec![&env, parent.clone()]);
+    assert_eq!(client.get_dependents(&parent), soroban_sdk::vec![&env, child.clone()]);
+
+    // Test: El delegado pide el libro; el préstamo queda a nombre del hijo
+    client.borrow_book_for(&parent, &child, &1);
+    let book = client.get_book(&1).unwrap();
+    assert_eq!(book.borrower, Some(child.clone()));
+    assert_eq!(client.get_active_loans(&child), 1);
+    assert_eq!(client.get_active_loans(&parent), 0);
+
+    // Test: Contadores y delegaciones viven en persistent storage con TTL renovado
+    let ttl = |key: DataKey| env.as_contract(&contract_id, || env.storage().persistent().get_ttl(&key));
+    assert_eq!(ttl(DataKey::ActiveLoans(child.clone())), TTL_EXTEND);
+    assert_eq!(ttl(DataKey::Delegates(child.clone())), TTL_EXTEND);
+    assert_eq!(ttl(DataKey::Dependents(parent.clone())), TTL_EXTEND);
+
+    // Test: Otros no pueden devolverlo por el hijo
+    let result = client.try_return_book_for(&stranger, &child, &1);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+
+    // Test: El delegado lo devuelve
+    client.return_book_for(&parent, &child, &1);
+    assert_eq!(client.get_book(&1).unwrap().status, BookStatus::Available);
+    assert_eq!(client.get_active_loans(&child), 0);
+
+    // Test: Al quitar la delegación el delegado pierde el permiso
+    client.remove_delegate(&child, &child, &parent);
+    let result = client.try_borrow_book_for(&parent, &child, &1);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+}
+
+#[test]
+fn test_dependent_loans_count_toward_delegate_limit() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let teacher = Address::generate(&env);
+    let student_a = Address::generate(&env);
+    let student_b = Address::generate(&env);
+    let token_id = env.register_stellar_asset_contract_v2(admin.clone()).address();
+
+    // Límite de 2 préstamos activos por miembro, membresía gratuita
+    client.initialize(&admin);
+    client.set_config(&LibraryConfig {
+        token: token_id,
+        late_fee_per_day: 0,
+        deposit: 0,
+        replacement_cost: 0,
+        membership_price: 0,
+        membership_period: 30 * 24 * 60 * 60,
+        max_loans: 2,
+    });
+    for member in [&teacher, &student_a, &student_b] {
+        client.subscribe(member, &1);
+    }
+    for _ in 0..3 {
+        client.add_book(&String::from_str(&env, "Libro"), &String::from_str(&env, "Autor"));
+    }
+
+    client.add_delegate(&student_a, &teacher);
+    client.add_delegate(&student_b, &teacher);
+
+    // Test: Los préstamos de los alumnos cuentan para el límite del docente
+    client.borrow_book_for(&teacher, &student_a, &1);
+    client.borrow_book(&student_b, &2);
+    let result = client.try_borrow_book(&teacher, &3);
+    assert_eq!(result, Err(Ok(LibraryError::LoanLimitReached)));
+    let result = client.try_borrow_book_for(&teacher, &student_a, &3);
+    assert_eq!(result, Err(Ok(LibraryError::LoanLimitReached)));
+
+    // Test: Al devolver un libro se libera un lugar
+    client.return_book(&student_b, &2);
+    client.borrow_book(&teacher, &3);
+    assert_eq!(client.get_active_loans(&teacher), 1);
+}
+
+/// Firma un KioskPayload como lo haría el kiosco fuera de la cadena
+fn sign_kiosk_payload(
+    env: &Env,
+    contract_id: &Address,
+    key: &SigningKey,
+    payload: &KioskPayload,
+) -> BytesN<64> {
+    let message = (contract_id.clone(), payload.clone()).to_xdr(env);
+    let message: std::vec::Vec<u8> = message.iter().collect();
+    BytesN::from_array(env, &key.sign(&message).to_bytes())
+}
+
+#[test]
+fn test_kiosk_checkout() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let member = Address::generate(&env);
+    let kiosk_key = SigningKey::from_bytes(&[7; 32]);
+    let kiosk = BytesN::from_array(&env, &kiosk_key.verifying_key().to_bytes());
+
+    client.initialize(&admin);
+    client.add_book(&String::from_str(&env, "Rayuela"), &String::from_str(&env, "Cortázar"));
+    client.add_book(&String::from_str(&env, "Ficciones"), &String::from_str(&env, "Borges"));
+
+    let payload = KioskPayload { kiosk: kiosk.clone(), member: member.clone(), book_id: 1, nonce: 0 };
+    let signature = sign_kiosk_payload(&env, &contract_id, &kiosk_key, &payload);
+
+    // Test: Un kiosco no registrado no puede prestar
+    let result = client.try_kiosk_checkout(&payload, &signature);
+    assert_eq!(result, Err(Ok(LibraryError::KioskNotFound)));
+
+    // Test: Con el kiosco registrado el préstamo queda a nombre del miembro
+    client.add_kiosk(&kiosk);
+    assert_eq!(client.get_kiosk_nonce(&kiosk), Some(0));
+    client.kiosk_checkout(&payload, &signature);
+    assert_eq!(client.get_book(&1).unwrap().borrower, Some(member.clone()));
+    assert_eq!(client.get_kiosk_nonce(&kiosk), Some(1));
+
+    // Test: La misma firma no puede repetirse
+    let result = client.try_kiosk_checkout(&payload, &signature);
+    assert_eq!(result, Err(Ok(LibraryError::InvalidNonce)));
+
+    // Test: Una firma que no corresponde al payload aborta la transacción
+    let forged = KioskPayload { book_id: 2, nonce: 1, ..payload.clone() };
+    assert!(client.try_kiosk_checkout(&forged, &signature).is_err());
+    assert_eq!(client.get_book(&2).unwrap().status, BookStatus::Available);
+
+    // Test: Otra llave no puede firmar por el kiosco
+    let other_key = SigningKey::from_bytes(&[9; 32]);
+    let signature = sign_kiosk_payload(&env, &contract_id, &other_key, &forged);
+    assert!(client.try_kiosk_checkout(&forged, &signature).is_err());
+
+    // Test: Un kiosco dado de baja ya no puede prestar
+    let signature = sign_kiosk_payload(&env, &contract_id, &kiosk_key, &forged);
+    client.remove_kiosk(&kiosk);
+    let result = client.try_kiosk_checkout(&forged, &signature);
+    assert_eq!(result, Err(Ok(LibraryError::KioskNotFound)));
+    assert_eq!(client.get_kiosk_nonce(&kiosk), None);
+
+    // Test: Al volver a registrarlo el nonce no se reinicia y la firma vieja sigue rechazada
+    client.return_book(&member, &1);
+    client.add_kiosk(&kiosk);
+    assert_eq!(client.get_kiosk_nonce(&kiosk), Some(1));
+    let old_signature = sign_kiosk_payload(&env, &contract_id, &kiosk_key, &payload);
+    let result = client.try_kiosk_checkout(&payload, &old_signature);
+    assert_eq!(result, Err(Ok(LibraryError::InvalidNonce)));
+    assert_eq!(client.get_book(&1).unwrap().status, BookStatus::Available);
+}
+
+#[test]
+fn test_reservation_hold_expires() {
+    let env = Env::default();
+    // La instancia del contrato debe sobrevivir a la reserva durante el test
+    env.ledger().with_mut(|li| li.min_persistent_entry_ttl = 2 * HOLD_LEDGERS);
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let reader = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    client.add_book(&String::from_str(&env, "Rayuela"), &String::from_str(&env, "Cortázar"));
+    client.add_book(&String::from_str(&env, "Ficciones"), &String::from_str(&env, "Borges"));
+    env.ledger().set_sequence_number(100);
+
+    // Test: La reserva vive en temporary storage con TTL igual a su duración
+    client.reserve_book(&reader, &1);
+    let hold = client.get_hold(&1).unwrap();
+    assert_eq!(hold.member, reader);
+    assert_eq!(hold.expires_at, 100 + HOLD_LEDGERS);
+    let ttl = env.as_contract(&contract_id, || {
+        env.storage().temporary().get_ttl(&DataKey::Hold(1))
+    });
+    assert_eq!(ttl, HOLD_LEDGERS);
+    assert_eq!(client.get_available_books(&None).len(), 1);
+
+    // Test: Mientras dure, nadie más puede llevarse el libro
+    let result = client.try_borrow_book(&other, &1);
+    assert_eq!(result, Err(Ok(LibraryError::BookNotAvailable)));
+
+    // Test: Al vencer, el libro vuelve a estar disponible sin limpieza
+    env.ledger().set_sequence_number(100 + HOLD_LEDGERS);
+    assert_eq!(client.get_hold(&1), None);
+    assert_eq!(client.get_book(&1).unwrap().status, BookStatus::Available);
+    client.borrow_book(&other, &1);
+    assert_eq!(client.get_book(&1).unwrap().borrower, Some(other.clone()));
+
+    // Test: Quien reservó puede llevarse el libro y la reserva se consume
+    client.reserve_book(&reader, &2);
+    client.borrow_book(&reader, &2);
+    assert_eq!(client.get_hold(&2), None);
+    assert_eq!(client.get_book(&2).unwrap().status, BookStatus::Borrowed);
+
+    // Test: Un libro con dueño no se puede reservar
+    let title = String::from_str(&env, "Aura");
+    let author = String::from_str(&env, "Fuentes");
+    let owned = client.mint_book(&other, &title, &author);
+    let result = client.try_reserve_book(&reader, &owned);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+
+    // Test: Un libro restringido no se puede reservar
+    let restricted = client.add_book(&title, &author);
+    client.set_book_restricted(&admin, &restricted, &true);
+    let result = client.try_reserve_book(&reader, &restricted);
+    assert_eq!(result, Err(Ok(LibraryError::Unauthorized)));
+    assert_eq!(client.get_hold(&restricted), None);
+}
+
+#[test]
+fn test_sweep_overdue() {
+    let env = Env::default();
+    let contract_id = env.register(LibraryContract, ());
+    let client = LibraryContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let member = Address::generate(&env);
+    let day = 24 * 60 * 60;
+
+    for _ in 0..4 {
+        client.add_book(&String::from_str(&env, "Libro"), &String::from_str(&env, "Autor"));
+    }
+    env.ledger().set_timestamp(1_000);
+    client.borrow_book(&member, &1);
+    client.borrow_book(&member, &3);
+    client.borrow_book(&member, &4);
+    assert_eq!(client.get_loan(&1).unwrap().due_at, 1_000 + LOAN_PERIOD);
+    assert_eq!(client.get_loan(&2), None);
+
+    // Test: Los préstamos viven en persistent storage con TTL renovado
+    let ttl = env.as_contract(&contract_id, || env.storage().persistent().get_ttl(&DataKey::Loan(1)));
+    assert_eq!(ttl, TTL_EXTEND);
+
+    // Test: Antes del vencimiento no hay recordatorios
+    assert_eq!(client.sweep_overdue(&10), 0);
+
+    // Test: Vencidos, el lote revisa solo 2 libros y guarda el cursor
+    client.return_book(&member, &4);
+    assert_eq!(client.get_loan(&4), None);
+    env.ledger().set_timestamp(1_000 + LOAN_PERIOD + day);
+    assert_eq!(client.sweep_overdue(&2), 1);
+    assert_eq!(
+        env.events().all(),
+        soroban_sdk::vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("overdue"), 1u32).into_val(&env),
+                (member.clone(), 1_000 + LOAN_PERIOD).into_val(&env),
+            ),
+        ]
+    );
+    assert!(client.get_loan(&1).unwrap().reminded);
+
+    // Test: La siguiente llamada continúa en el libro 3 y luego da la vuelta
+    assert_eq!(client.sweep_overdue(&2), 1);
+    assert!(client.get_loan(&3).unwrap().reminded);
+
+    // Test: Un préstamo ya recordado no se repite
+    assert_eq!(client.sweep_overdue(&10), 0);
+
+    // Test: Al devolverlo se borra el préstamo
+    client.return_book(&member, &1);
+    assert_eq!(client.get_loan(&1), None);
+}
@@ -0,0 +1,249 @@
+#![cfg(test)]
+extern crate std;
+
+// =============================================================================
+// TESTS BASADOS EN MODELO
+// Generamos secuencias aleatorias de operaciones, las aplicamos al contrato y a
+// un modelo en memoria muy simple, y comparamos resultados e invariantes
+// =============================================================================
+
+use super::*;
+use proptest::prelude::*;
+use soroban_sdk::{testutils::Address as _, testutils::EnvTestConfig, Address, Env, String};
+use std::vec::Vec as StdVec;
+
+// Cantidad de miembros que participan y mayor ID de libro que se genera
+// (los IDs que aún no existen sirven para probar BookNotFound)
+const MEMBERS: usize = 3;
+const MAX_BOOK_ID: u32 = 8;
+
+/// Operación generada al azar
+#[derive(Clone, Debug)]
+enum Op {
+    AddBook,
+    Borrow(usize, u32),
+    Return(usize, u32),
+    Reserve(usize, u32),
+    ChangeStatus(u32, BookStatus),
+}
+
+/// Libro tal como lo ve el modelo
+#[derive(Clone, Debug)]
+struct ModelBook {
+    status: BookStatus,       // Estado guardado (sin aplicar la reserva)
+    borrower: Option<usize>,  // Índice del miembro que lo tiene
+    hold: Option<usize>,      // Índice del miembro que lo reservó
+}
+
+impl ModelBook {
+    /// Estado que reporta get_book(): un libro disponible con reserva se ve reservado
+    fn visible_status(&self) -> BookStatus {
+        if self.status == BookStatus::Available && self.hold.is_some() {
+            BookStatus::Reserved
+        } else {
+            self.status.clone()
+        }
+    }
+}
+
+/// Modelo de referencia de la biblioteca (sin configuración: membresía libre
+/// y DEFAULT_MAX_LOANS préstamos por miembro)
+#[derive(Default)]
+struct Model {
+    books: StdVec<ModelBook>,
+    loans: [u32; MEMBERS],
+}
+
+impl Model {
+    fn book(&mut self, book_id: u32) -> Result<&mut ModelBook, LibraryError> {
+        if book_id == 0 {
+            return Err(LibraryError::BookNotFound);
+        }
+        self.books
+            .get_mut(book_id as usize - 1)
+            .ok_or(LibraryError::BookNotFound)
+    }
+
+    fn add_book(&mut self) -> u32 {
+        self.books.push(ModelBook {
+            status: BookStatus::Available,
+            borrower: None,
+            hold: None,
+        });
+        self.books.len() as u32
+    }
+
+    fn borrow(&mut self, member: usize, book_id: u32) -> Result<(), LibraryError> {
+        let loans = self.loans[member];
+        let book = self.book(book_id)?;
+        if book.status != BookStatus::Available {
+            return Err(LibraryError::BookNotAvailable);
+        }
+        if loans >= DEFAULT_MAX_LOANS {
+            return Err(LibraryError::LoanLimitReached);
+        }
+        if book.hold.is_some_and(|holder| holder != member) {
+            return Err(LibraryError::BookNotAvailable);
+        }
+        book.hold = None;
+        book.status = BookStatus::Borrowed;
+        book.borrower = Some(member);
+        self.loans[member] += 1;
+        Ok(())
+    }
+
+    fn return_book(&mut self, member: usize, book_id: u32) -> Result<(), LibraryError> {
+        let book = self.book(book_id)?;
+        if book.status != BookStatus::Borrowed {
+            return Err(LibraryError::BookNotAvailable);
+        }
+        if book.borrower != Some(member) {
+            return Err(LibraryError::Unauthorized);
+        }
+        let released = book.borrower.take();
+        book.status = BookStatus::Available;
+        if let Some(borrower) = released {
+            self.loans[borrower] -= 1;
+        }
+        Ok(())
+    }
+
+    fn reserve(&mut self, member: usize, book_id: u32) -> Result<(), LibraryError> {
+        let book = self.book(book_id)?;
+        if book.visible_status() != BookStatus::Available {
+            return Err(LibraryError::BookNotAvailable);
+        }
+        book.hold = Some(member);
+        Ok(())
+    }
+
+    fn change_status(&mut self, book_id: u32, status: BookStatus) -> Result<(), LibraryError> {
+        let book = self.book(book_id)?;
+        let managed = |status: &BookStatus| {
+            matches!(status, BookStatus::Borrowed | BookStatus::InTransit)
+        };
+        if managed(&book.status) || managed(&status) {
+            return Err(LibraryError::BookNotAvailable);
+        }
+        book.status = status;
+        Ok(())
+    }
+}
+
+fn status_strategy() -> impl Strategy<Value = BookStatus> {
+    prop_oneof![
+        Just(BookStatus::Available),
+        Just(BookStatus::Borrowed),
+        Just(BookStatus::Reserved),
+        Just(BookStatus::InTransit),
+    ]
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    let member = 0..MEMBERS;
+    let book_id = 0..=MAX_BOOK_ID;
+    prop_oneof![
+        2 => Just(Op::AddBook),
+        3 => (member.clone(), book_id.clone()).prop_map(|(m, b)| Op::Borrow(m, b)),
+        3 => (member.clone(), book_id.clone()).prop_map(|(m, b)| Op::Return(m, b)),
+        2 => (member, book_id.clone()).prop_map(|(m, b)| Op::Reserve(m, b)),
+        1 => (book_id, status_strategy()).prop_map(|(b, s)| Op::ChangeStatus(b, s)),
+    ]
+}
+
+/// Convierte el resultado de un try_ del cliente en el mismo tipo que el modelo.
+/// Un error que no sea un LibraryError (un panic del contrato) hace fallar el test
+fn contract_result<T, C: core::fmt::Debug, I: core::fmt::Debug>(
+    result: Result<Result<T, C>, Result<LibraryError, I>>,
+) -> Result<(), LibraryError> {
+    match result {
+        Ok(value) => {
+            value.expect("el valor de retorno no se pudo convertir");
+            Ok(())
+        }
+        Err(error) => Err(error.expect("el contrato falló sin un LibraryError")),
+    }
+}
+
+/// Compara el contrato con el modelo y verifica invariantes
+fn check_invariants(client: &LibraryContractClient, members: &[Address], model: &Model) {
+    let all_books = client.get_all_books();
+    assert_eq!(all_books.len() as usize, model.books.len());
+
+    for (index, expected) in model.books.iter().enumerate() {
+        let book = client.get_book(&(index as u32 + 1)).unwrap();
+        assert_eq!(book.status, expected.visible_status());
+        assert_eq!(book.borrower, expected.borrower.map(|m| members[m].clone()));
+        assert_eq!(all_books.get(index as u32).unwrap(), book);
+    }
+
+    // La cantidad de disponibles coincide con los estados Available
+    let available = client.get_available_books(&None);
+    let expected_available = model
+        .books
+        .iter()
+        .filter(|book| book.visible_status() == BookStatus::Available)
+        .count();
+    assert_eq!(available.len() as usize, expected_available);
+    assert!(available.iter().all(|book| book.status == BookStatus::Available));
+
+    // Los contadores de préstamos coinciden y suman los libros con prestatario
+    let mut total_loans = 0;
+    for (index, member) in members.iter().enumerate() {
+        let loans = client.get_active_loans(member);
+        assert_eq!(loans, model.loans[index]);
+        assert!(loans <= DEFAULT_MAX_LOANS);
+        total_loans += loans;
+    }
+    let borrowed = all_books.iter().filter(|book| book.borrower.is_some()).count();
+    assert_eq!(total_loans as usize, borrowed);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn test_library_matches_model(ops in prop::collection::vec(op_strategy(), 1..40)) {
+        // Sin snapshots: cada caso generado crearía su propio archivo
+        let env = Env::new_with_config(EnvTestConfig {
+            capture_snapshot_at_drop: false,
+        });
+        let contract_id = env.register(LibraryContract, ());
+        let client = LibraryContractClient::new(&env, &contract_id);
+        env.mock_all_auths();
+        let librarian = Address::generate(&env);
+        client.initialize(&librarian);
+        let members: StdVec<Address> = (0..MEMBERS).map(|_| Address::generate(&env)).collect();
+        let mut model = Model::default();
+
+        for op in ops {
+            match op {
+                Op::AddBook => {
+                    let id = client.add_book(
+                        &String::from_str(&env, "Libro"),
+                        &String::from_str(&env, "Autor"),
+                    );
+                    prop_assert_eq!(id, model.add_book());
+                }
+                Op::Borrow(member, book_id) => {
+                    let result = client.try_borrow_book(&members[member], &book_id);
+                    prop_assert_eq!(contract_result(result), model.borrow(member, book_id));
+                }
+                Op::Return(member, book_id) => {
+                    let result = client.try_return_book(&members[member], &book_id);
+                    prop_assert_eq!(contract_result(result), model.return_book(member, book_id));
+                }
+                Op::Reserve(member, book_id) => {
+                    let result = client.try_reserve_book(&members[member], &book_id);
+                    prop_assert_eq!(contract_result(result), model.reserve(member, book_id));
+                }
+                Op::ChangeStatus(book_id, status) => {
+                    let result = client.try_change_book_status(&librarian, &book_id, &status);
+                    prop_assert_eq!(contract_result(result), model.change_status(book_id, status));
+                }
+            }
+
+            check_invariants(&client, &members, &model);
+        }
+    }
+}